@@ -3,7 +3,7 @@ mod app;
 use std::io;
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -43,13 +43,13 @@ fn main() -> Result<()> {
 
 fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
     loop {
-        terminal.draw(|f| app.draw::<B>(f))?;
+        terminal.draw(|f| app.draw(f))?;
 
-        if event::poll(app.tick_rate)? {
-            if let event::Event::Key(key) = event::read()? {
-                // Pass key event to app
-                app.handle_event(event::Event::Key(key))?;
-            }
+        if event::poll(app.tick_rate)?
+            && let event::Event::Key(key) = event::read()?
+        {
+            // Pass key event to app
+            app.handle_event(event::Event::Key(key))?;
         }
 
         if !app.running {
@@ -0,0 +1,640 @@
+//! Color math shared by the visual effects (fade, gradient).
+
+use ratatui::style::{Color, Modifier, Style};
+
+/// Color capabilities of the target terminal, used by the visual effects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorDepth {
+    /// 24-bit color: effects compute exact RGB values.
+    #[default]
+    TrueColor,
+    /// 256 indexed colors: computed RGB colors are quantized to the nearest
+    /// color of the xterm 6×6×6 cube or grayscale ramp.
+    Ansi256,
+    /// 16 ANSI colors: named colors are dimmed through the bright/normal/dark
+    /// variants of their hue, preserving the terminal's theme palette, and
+    /// computed RGB colors are quantized to the nearest named color.
+    Ansi16,
+}
+
+/// Color space the visual effects dim and blend colors in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    /// Scales the raw RGB channels: cheap, but dark colors lose saturation unevenly.
+    #[default]
+    Rgb,
+    /// Scales the HSL lightness, keeping hue and saturation.
+    Hsl,
+    /// Scales the OKLCH lightness, keeping the hue: perceptually even steps.
+    Oklch,
+}
+
+/// Ready-made colormaps for heat and intensity data, usable as gradient
+/// stops (see [`WaveformWidget::colormap`](crate::WaveformWidget::colormap)).
+///
+/// On 256- and 16-color terminals the blended colors are quantized to the
+/// nearest indexed color, see [`ColorDepth`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colormap {
+    /// Perceptually uniform blue → green → yellow, readable in grayscale and by colorblind viewers.
+    Viridis,
+    /// Perceptually uniform black → purple → orange → pale yellow.
+    Magma,
+    /// Perceptually uniform blue → magenta → yellow.
+    Plasma,
+    /// High-contrast rainbow (dark blue → cyan → green → yellow → red), for fine detail.
+    Turbo,
+}
+
+impl Colormap {
+    /// Color stops sampled evenly over the colormap, from 0.0 to 1.0.
+    pub fn stops(self) -> [(f64, Color); 9] {
+        let hex = match self {
+            Colormap::Viridis => [
+                0x440154, 0x472c7a, 0x3b518b,
+                0x2c718e, 0x21908d, 0x27ad81,
+                0x5cc863, 0xaadc32, 0xfde725,
+            ],
+            Colormap::Magma => [
+                0x000004, 0x1c1044, 0x4f127b,
+                0x812581, 0xb5367a, 0xe55064,
+                0xfb8761, 0xfec287, 0xfcfdbf,
+            ],
+            Colormap::Plasma => [
+                0x0d0887, 0x4c02a1, 0x7e03a8,
+                0xa92395, 0xcc4778, 0xe56b5d,
+                0xf89441, 0xfdc328, 0xf0f921,
+            ],
+            Colormap::Turbo => [
+                0x30123b, 0x4662d7, 0x36aaf9,
+                0x1ae4b6, 0x72fe5e, 0xc8ef34,
+                0xfaba39, 0xf66b19, 0x7a0403,
+            ],
+        };
+        std::array::from_fn(|i| (i as f64 / 8.0, Color::from_u32(hex[i])))
+    }
+
+    /// Color at `position` (0.0 - 1.0) for a terminal of the given depth.
+    pub fn color(self, position: f64, depth: ColorDepth) -> Color {
+        let color = color_at(&self.stops(), position.clamp(0.0, 1.0), ColorDepth::TrueColor, ColorSpace::Rgb);
+        match color {
+            Some(Color::Rgb(r, g, b)) => quantize_color((r, g, b), depth),
+            color => color.unwrap_or(Color::Reset),
+        }
+    }
+}
+
+/// Dims the foreground for the horizontal fade (`factor` 1.0 = unchanged).
+pub(crate) fn fade(style: Style, factor: f64, depth: ColorDepth, space: ColorSpace) -> Style {
+    if style.fg == Some(Color::Reset) {
+        return reset_dim(style, factor);
+    }
+    match named_dim(style, factor, depth) {
+        Some(style) => style,
+        None if space == ColorSpace::Rgb => apply_fade(style, factor),
+        None => dim(style, factor, space),
+    }
+}
+
+/// Dims the foreground for the vertical gradient (`ratio` 0.0 at center, 1.0 at
+/// peak), by up to `strength` (0.0 - 1.0) at the peak.
+pub(crate) fn gradient(style: Style, ratio: f64, strength: f64, depth: ColorDepth, space: ColorSpace) -> Style {
+    let brightness = gradient_brightness(ratio, strength);
+    if style.fg == Some(Color::Reset) {
+        return reset_dim(style, brightness);
+    }
+    match named_dim(style, brightness, depth) {
+        Some(style) => style,
+        None if space == ColorSpace::Rgb => apply_gradient(style, ratio, strength),
+        None => dim(style, brightness, space),
+    }
+}
+
+/// The terminal's default foreground has no known RGB value: it is kept, and
+/// marked [`Modifier::DIM`] once dimmed below half brightness.
+fn reset_dim(style: Style, factor: f64) -> Style {
+    if factor < 0.5 { style.add_modifier(Modifier::DIM) } else { style }
+}
+
+/// Scales the lightness of the foreground by `factor` in a perceptual space.
+fn dim(style: Style, factor: f64, space: ColorSpace) -> Style {
+    let Some(color) = style.fg else {
+        return style;
+    };
+    let rgb = color_to_rgb(color);
+    let factor = factor.clamp(0.0, 1.0);
+    let (r, g, b) = match space {
+        ColorSpace::Rgb => return apply_fade(style, factor),
+        ColorSpace::Hsl => {
+            let (h, s, l) = rgb_to_hsl(rgb);
+            hsl_to_rgb((h, s, l * factor))
+        }
+        ColorSpace::Oklch => {
+            // Chroma shrinks with the lightness so dark colors stay in gamut.
+            let (l, a, b) = rgb_to_oklab(rgb);
+            oklab_to_rgb((l * factor, a * factor, b * factor))
+        }
+    };
+    style.fg(Color::Rgb(r, g, b))
+}
+
+/// Color at `position` along the gradient `stops` (sorted by position).
+///
+/// True color terminals get the RGB blend of the two surrounding stops; on
+/// 16-color terminals the nearest stop is used as-is, keeping named colors.
+/// So is a stop in the terminal's default color (`Color::Reset`), which has
+/// no RGB value to blend.
+pub(crate) fn color_at(stops: &[(f64, Color)], position: f64, depth: ColorDepth, space: ColorSpace) -> Option<Color> {
+    let next = stops.iter().position(|&(at, _)| at >= position);
+    let (lo, hi) = match next {
+        None => return stops.last().map(|&(_, color)| color),
+        Some(0) => return Some(stops[0].1),
+        Some(i) => (stops[i - 1], stops[i]),
+    };
+    let t = (position - lo.0) / (hi.0 - lo.0).max(f64::EPSILON);
+    if depth == ColorDepth::Ansi16 || lo.1 == Color::Reset || hi.1 == Color::Reset {
+        return Some(if t < 0.5 { lo.1 } else { hi.1 });
+    }
+    let (lo, hi) = (color_to_rgb(lo.1), color_to_rgb(hi.1));
+    let mix = |a: f64, b: f64| a + (b - a) * t;
+    let (r, g, b) = match space {
+        ColorSpace::Rgb => {
+            let mix = |a: u8, b: u8| mix(a as f64, b as f64).round() as u8;
+            (mix(lo.0, hi.0), mix(lo.1, hi.1), mix(lo.2, hi.2))
+        }
+        ColorSpace::Hsl => {
+            let ((h1, s1, l1), (h2, s2, l2)) = (rgb_to_hsl(lo), rgb_to_hsl(hi));
+            // Hue takes the short way around the wheel.
+            let dh = (h2 - h1 + 540.0) % 360.0 - 180.0;
+            hsl_to_rgb(((h1 + dh * t).rem_euclid(360.0), mix(s1, s2), mix(l1, l2)))
+        }
+        ColorSpace::Oklch => {
+            let ((l1, a1, b1), (l2, a2, b2)) = (rgb_to_oklab(lo), rgb_to_oklab(hi));
+            oklab_to_rgb((mix(l1, l2), mix(a1, a2), mix(b1, b2)))
+        }
+    };
+    Some(Color::Rgb(r, g, b))
+}
+
+/// HSL of an sRGB color: hue in degrees, saturation and lightness 0.0 - 1.0.
+fn rgb_to_hsl((r, g, b): (u8, u8, u8)) -> (f64, f64, f64) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0.0, 0.0, l);
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h * 60.0, s, l)
+}
+
+fn hsl_to_rgb((h, s, l): (f64, f64, f64)) -> (u8, u8, u8) {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let channel = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+/// OKLab of an sRGB color (see <https://bottosson.github.io/posts/oklab/>).
+fn rgb_to_oklab((r, g, b): (u8, u8, u8)) -> (f64, f64, f64) {
+    let linear = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    };
+    let (r, g, b) = (linear(r), linear(g), linear(b));
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    (
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    )
+}
+
+fn oklab_to_rgb((l, a, b): (f64, f64, f64)) -> (u8, u8, u8) {
+    let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+    let srgb = |c: f64| {
+        let c = c.clamp(0.0, 1.0);
+        let c = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+        (c * 255.0).round() as u8
+    };
+    (
+        srgb(4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_),
+        srgb(-1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_),
+        srgb(-0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_),
+    )
+}
+
+/// Quantizes an RGB foreground to the nearest color the terminal can display.
+pub(crate) fn quantize(style: Style, depth: ColorDepth) -> Style {
+    match style.fg {
+        Some(Color::Rgb(r, g, b)) => style.fg(quantize_color((r, g, b), depth)),
+        _ => style,
+    }
+}
+
+fn quantize_color(rgb: (u8, u8, u8), depth: ColorDepth) -> Color {
+    let distance = |(r, g, b): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, rgb.0) + d(g, rgb.1) + d(b, rgb.2)
+    };
+    match depth {
+        ColorDepth::TrueColor => Color::Rgb(rgb.0, rgb.1, rgb.2),
+        ColorDepth::Ansi16 => ANSI16.into_iter().min_by_key(|&c| distance(color_to_rgb(c))).unwrap_or(Color::Reset),
+        ColorDepth::Ansi256 => {
+            // Nearest cube level per channel, or the nearest gray.
+            const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+            let level = |c: u8| (0..6).min_by_key(|&i| (LEVELS[i] as i32 - c as i32).abs()).unwrap_or(0);
+            let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+            let cube = (16 + 36 * r + 6 * g + b) as u8;
+            let cube_rgb = (LEVELS[r], LEVELS[g], LEVELS[b]);
+
+            let mean = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+            let gray = (mean.saturating_sub(3) / 10).min(23) as u8;
+            let gray_value = 8 + 10 * gray;
+            if distance((gray_value, gray_value, gray_value)) < distance(cube_rgb) {
+                Color::Indexed(232 + gray)
+            } else {
+                Color::Indexed(cube)
+            }
+        }
+    }
+}
+
+/// On 16-color terminals, dims named colors by stepping down their hue's ramp.
+/// Other colors are first snapped to the nearest named color, since an RGB
+/// fade would only be quantized back to it. Between the bright and the normal
+/// variant, the ramp has the normal one with [`Modifier::BOLD`], which most
+/// terminals show brighter; steps past the hue (dark gray and black) get
+/// [`Modifier::DIM`]. Undimmed styles are kept as given.
+fn named_dim(style: Style, factor: f64, depth: ColorDepth) -> Option<Style> {
+    if depth != ColorDepth::Ansi16 {
+        return None;
+    }
+    if factor >= 1.0 {
+        return Some(style);
+    }
+    let fg = match style.fg? {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => quantize_color((r, g, b), depth),
+        Color::Indexed(i) if i >= 16 => quantize_color(color_to_rgb(Color::Indexed(i)), depth),
+        color => color,
+    };
+    let (ramp, start) = named_ramp(fg)?;
+    // Bright colors split their first band between the bright and the bold normal variant.
+    let bands: &[f64] = if start == 0 { &[0.9, 0.75, 0.45, 0.2] } else { &[0.75, 0.45, 0.2] };
+    let steps = bands.iter().take_while(|&&band| factor < band).count();
+    let (color, modifier) = ramp[(start + steps).min(ramp.len() - 1)];
+    Some(style.fg(color).add_modifier(modifier))
+}
+
+/// The bright → normal → dark ramp of a named color, with the modifier of each
+/// step, and the color's position on it.
+fn named_ramp(color: Color) -> Option<([(Color, Modifier); 5], usize)> {
+    let ramp = |bright: Color, normal: Color| {
+        [
+            (bright, Modifier::empty()),
+            (normal, Modifier::BOLD),
+            (normal, Modifier::empty()),
+            (Color::DarkGray, Modifier::DIM),
+            (Color::Black, Modifier::DIM),
+        ]
+    };
+    Some(match color {
+        Color::LightRed => (ramp(Color::LightRed, Color::Red), 0),
+        Color::Red => (ramp(Color::LightRed, Color::Red), 2),
+        Color::LightGreen => (ramp(Color::LightGreen, Color::Green), 0),
+        Color::Green => (ramp(Color::LightGreen, Color::Green), 2),
+        Color::LightYellow => (ramp(Color::LightYellow, Color::Yellow), 0),
+        Color::Yellow => (ramp(Color::LightYellow, Color::Yellow), 2),
+        Color::LightBlue => (ramp(Color::LightBlue, Color::Blue), 0),
+        Color::Blue => (ramp(Color::LightBlue, Color::Blue), 2),
+        Color::LightMagenta => (ramp(Color::LightMagenta, Color::Magenta), 0),
+        Color::Magenta => (ramp(Color::LightMagenta, Color::Magenta), 2),
+        Color::LightCyan => (ramp(Color::LightCyan, Color::Cyan), 0),
+        Color::Cyan => (ramp(Color::LightCyan, Color::Cyan), 2),
+        Color::White => (ramp(Color::White, Color::Gray), 0),
+        Color::Gray => (ramp(Color::White, Color::Gray), 2),
+        Color::DarkGray => (ramp(Color::White, Color::Gray), 3),
+        Color::Black => (ramp(Color::White, Color::Gray), 4),
+        Color::Indexed(i) if i < 16 => return named_ramp(ANSI16[i as usize]),
+        _ => return None,
+    })
+}
+
+/// Named equivalents of the first 16 indexed colors.
+const ANSI16: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// Default dimming of the gradient at the peak.
+pub(crate) const GRADIENT_STRENGTH: f64 = 0.7;
+
+/// Brightness of the gradient at `ratio` (center = 1.0, peak = 1.0 - strength).
+fn gradient_brightness(ratio: f64, strength: f64) -> f64 {
+    1.0 - (ratio * strength)
+}
+
+pub(crate) fn apply_fade(style: Style, factor: f64) -> Style {
+    // Removed early return to ensure consistent RGB conversion
+    // even when factor is 1.0. This prevents "Named Color" vs "RGB Color" mismatches.
+    
+    // Apply DIM modifier for extra fading hint
+    // Removed DIM modifier as it might cause desaturation on some terminals
+    // if factor < 0.5 {
+    //    style = style.add_modifier(ratatui::style::Modifier::DIM);
+    // }
+    
+    let (r, g, b) = match style.fg {
+        Some(c) => color_to_rgb(c),
+        None => return style,
+    };
+
+    let new_r = (r as f64 * factor) as u8;
+    let new_g = (g as f64 * factor) as u8;
+    let new_b = (b as f64 * factor) as u8;
+
+    style.fg(Color::Rgb(new_r, new_g, new_b))
+}
+
+pub(crate) fn color_to_rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(i) => {
+             match i {
+                 // Standard 16 colors approximation
+                 0 => (0, 0, 0), // Black
+                 1 => (170, 0, 0), // Red
+                 2 => (0, 170, 0), // Green
+                 3 => (170, 85, 0), // Yellow
+                 4 => (0, 0, 170), // Blue
+                 5 => (170, 0, 170), // Magenta
+                 6 => (0, 170, 170), // Cyan
+                 7 => (170, 170, 170), // Gray
+                 8 => (85, 85, 85), // DarkGray
+                 9 => (255, 85, 85), // LightRed
+                 10 => (85, 255, 85), // LightGreen
+                 11 => (255, 255, 85), // LightYellow
+                 12 => (85, 85, 255), // LightBlue
+                 13 => (255, 85, 255), // LightMagenta
+                 14 => (85, 255, 255), // LightCyan
+                 15 => (255, 255, 255), // White
+                 // 6x6x6 color cube
+                 16..=231 => {
+                     const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+                     let i = i - 16;
+                     (LEVELS[(i / 36) as usize], LEVELS[(i / 6 % 6) as usize], LEVELS[(i % 6) as usize])
+                 }
+                 // Grayscale ramp
+                 _ => {
+                     let v = 8 + 10 * (i - 232);
+                     (v, v, v)
+                 }
+             }
+        },
+        Color::Black => (0, 0, 0),
+        Color::Red => (170, 0, 0),
+        Color::Green => (0, 170, 0),
+        Color::Yellow => (170, 85, 0),
+        Color::Blue => (0, 0, 170),
+        Color::Magenta => (170, 0, 170),
+        Color::Cyan => (0, 170, 170),
+        Color::Gray => (170, 170, 170),
+        Color::DarkGray => (85, 85, 85),
+        Color::LightRed => (255, 85, 85),
+        Color::LightGreen => (85, 255, 85),
+        Color::LightYellow => (255, 255, 85),
+        Color::LightBlue => (85, 85, 255),
+        Color::LightMagenta => (255, 85, 255),
+        Color::LightCyan => (85, 255, 255),
+        Color::White => (255, 255, 255),
+        _ => (255, 255, 255),
+    }
+}
+
+pub(crate) fn apply_gradient(style: Style, ratio: f64, strength: f64) -> Style {
+    // Inverted Gradient:
+    // Center (ratio 0.0) = Full Brightness (1.0)
+    // Peak (ratio 1.0) = Dimmer (30% brightness at the default strength)
+    
+    if let Some(color) = style.fg {
+        let (r, g, b) = color_to_rgb(color);
+
+        // Brightness decreases as we go away from center
+        let brightness = gradient_brightness(ratio, strength);
+        
+        let new_r = (r as f64 * brightness) as u8;
+        let new_g = (g as f64 * brightness) as u8;
+        let new_b = (b as f64 * brightness) as u8;
+        
+        style.fg(Color::Rgb(new_r, new_g, new_b))
+    } else {
+        style
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_fade() {
+        let style = Style::default().fg(Color::Rgb(100, 200, 50));
+        
+        // 100% factor -> Same color
+        let faded_100 = apply_fade(style, 1.0);
+        assert_eq!(faded_100.fg, Some(Color::Rgb(100, 200, 50)));
+
+        // 50% factor -> Half brightness
+        let faded_50 = apply_fade(style, 0.5);
+        assert_eq!(faded_50.fg, Some(Color::Rgb(50, 100, 25)));
+
+        // 0% factor -> Black
+        let faded_0 = apply_fade(style, 0.0);
+        assert_eq!(faded_0.fg, Some(Color::Rgb(0, 0, 0)));
+    }
+
+    #[test]
+    fn test_apply_gradient() {
+        let style = Style::default().fg(Color::Rgb(0, 0, 255)); // Blue
+        
+        // 0% ratio (Center) -> Full Brightness
+        // B: 255 * 1.0 = 255
+        let grad_0 = apply_gradient(style, 0.0, GRADIENT_STRENGTH);
+        assert_eq!(grad_0.fg, Some(Color::Rgb(0, 0, 255)));
+
+        // 100% ratio (Peak) -> Dimmer (30% brightness)
+        // B: 255 * 0.3 = 76.5 -> 76
+        let grad_100 = apply_gradient(style, 1.0, GRADIENT_STRENGTH);
+        assert_eq!(grad_100.fg, Some(Color::Rgb(0, 0, 76)));
+
+        // Strength 0.2 -> 80% brightness at the peak
+        let subtle = apply_gradient(style, 1.0, 0.2);
+        assert_eq!(subtle.fg, Some(Color::Rgb(0, 0, 204)));
+    }
+
+    #[test]
+    fn test_color_at() {
+        let stops = [(0.0, Color::Rgb(0, 200, 0)), (0.5, Color::Rgb(200, 200, 0)), (1.0, Color::Red)];
+        let at = |position, depth| color_at(&stops, position, depth, ColorSpace::Rgb);
+        assert_eq!(at(0.25, ColorDepth::TrueColor), Some(Color::Rgb(100, 200, 0)));
+        assert_eq!(at(0.75, ColorDepth::TrueColor), Some(Color::Rgb(185, 100, 0)));
+        assert_eq!(at(2.0, ColorDepth::TrueColor), Some(Color::Red));
+        assert_eq!(at(0.8, ColorDepth::Ansi16), Some(Color::Red));
+        assert_eq!(color_at(&[], 0.5, ColorDepth::TrueColor, ColorSpace::Rgb), None);
+    }
+
+    #[test]
+    fn test_named_dim() {
+        let style = Style::default().fg(Color::LightGreen);
+        assert_eq!(fade(style, 1.0, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::LightGreen));
+        assert_eq!(fade(style, 0.8, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::Green));
+        assert_eq!(fade(style, 0.5, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::Green));
+        assert_eq!(fade(style, 0.3, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::DarkGray));
+        assert_eq!(fade(style, 0.0, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::Black));
+
+        // Normal variants start one step down; indexed colors map to their names.
+        let style = Style::default().fg(Color::Indexed(6));
+        assert_eq!(gradient(style, 0.5, GRADIENT_STRENGTH, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::DarkGray));
+        assert_eq!(gradient(style, 0.2, GRADIENT_STRENGTH, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::Cyan));
+
+        // True color terminals keep the RGB math.
+        let style = Style::default().fg(Color::Green);
+        assert_eq!(fade(style, 1.0, ColorDepth::TrueColor, ColorSpace::Rgb).fg, Some(Color::Rgb(0, 170, 0)));
+    }
+
+    #[test]
+    fn test_color_space() {
+        // Round trips are exact up to rounding.
+        for rgb in [(0, 0, 0), (255, 255, 255), (200, 40, 90), (12, 180, 250)] {
+            assert_eq!(hsl_to_rgb(rgb_to_hsl(rgb)), rgb);
+            assert_eq!(oklab_to_rgb(rgb_to_oklab(rgb)), rgb);
+        }
+
+        // HSL keeps hue and saturation: a dimmed pure color stays pure.
+        let style = Style::default().fg(Color::Rgb(255, 0, 0));
+        assert_eq!(fade(style, 0.5, ColorDepth::TrueColor, ColorSpace::Hsl).fg, Some(Color::Rgb(128, 0, 0)));
+
+        // OKLCH halves the perceived lightness, which takes darker channels than halving them.
+        let style = Style::default().fg(Color::Rgb(100, 200, 50));
+        let Some(Color::Rgb(_, g, _)) = fade(style, 0.5, ColorDepth::TrueColor, ColorSpace::Oklch).fg else {
+            panic!("expected an RGB color");
+        };
+        assert!(g < 100);
+        assert_eq!(fade(style, 1.0, ColorDepth::TrueColor, ColorSpace::Oklch).fg, Some(Color::Rgb(100, 200, 50)));
+
+        // Blending through HSL keeps the full saturation halfway between red and green.
+        let stops = [(0.0, Color::Rgb(255, 0, 0)), (1.0, Color::Rgb(0, 255, 0))];
+        assert_eq!(color_at(&stops, 0.5, ColorDepth::TrueColor, ColorSpace::Hsl), Some(Color::Rgb(255, 255, 0)));
+        assert_eq!(color_at(&stops, 0.5, ColorDepth::TrueColor, ColorSpace::Rgb), Some(Color::Rgb(128, 128, 0)));
+    }
+
+    #[test]
+    fn test_quantize() {
+        let rgb = |r, g, b| Style::default().fg(Color::Rgb(r, g, b));
+        assert_eq!(quantize(rgb(255, 0, 0), ColorDepth::Ansi256).fg, Some(Color::Indexed(196)));
+        assert_eq!(quantize(rgb(100, 140, 0), ColorDepth::Ansi256).fg, Some(Color::Indexed(64)));
+        assert_eq!(quantize(rgb(128, 128, 130), ColorDepth::Ansi256).fg, Some(Color::Indexed(244)));
+        assert_eq!(quantize(rgb(0, 160, 10), ColorDepth::Ansi16).fg, Some(Color::Green));
+        assert_eq!(quantize(rgb(10, 20, 30), ColorDepth::TrueColor).fg, Some(Color::Rgb(10, 20, 30)));
+
+        // Named colors are already displayable.
+        let style = Style::default().fg(Color::Cyan);
+        assert_eq!(quantize(style, ColorDepth::Ansi256), style);
+    }
+
+    #[test]
+    fn test_reset_color() {
+        let style = Style::default().fg(Color::Reset);
+        assert_eq!(fade(style, 0.8, ColorDepth::TrueColor, ColorSpace::Rgb), style);
+        assert_eq!(fade(style, 0.3, ColorDepth::Ansi16, ColorSpace::Oklch), style.add_modifier(Modifier::DIM));
+        assert_eq!(gradient(style, 1.0, GRADIENT_STRENGTH, ColorDepth::TrueColor, ColorSpace::Rgb), style.add_modifier(Modifier::DIM));
+
+        let stops = [(0.0, Color::Reset), (1.0, Color::Red)];
+        assert_eq!(color_at(&stops, 0.3, ColorDepth::TrueColor, ColorSpace::Rgb), Some(Color::Reset));
+        assert_eq!(color_at(&stops, 0.7, ColorDepth::TrueColor, ColorSpace::Rgb), Some(Color::Red));
+    }
+
+    #[test]
+    fn test_colormap() {
+        let stops = Colormap::Viridis.stops();
+        assert_eq!(stops[0], (0.0, Color::Rgb(0x44, 0x01, 0x54)));
+        assert_eq!(stops[8], (1.0, Color::Rgb(0xfd, 0xe7, 0x25)));
+
+        assert_eq!(Colormap::Magma.color(0.0, ColorDepth::TrueColor), Color::Rgb(0, 0, 4));
+        assert_eq!(Colormap::Magma.color(0.0, ColorDepth::Ansi256), Color::Indexed(16));
+        assert_eq!(Colormap::Turbo.color(2.0, ColorDepth::Ansi256), Color::Indexed(88));
+        assert_eq!(Colormap::Plasma.color(1.0, ColorDepth::Ansi16), Color::LightYellow);
+    }
+
+    #[test]
+    fn test_ansi16_fallback() {
+        // RGB and 256-color colors snap to their nearest named color, then step down its ramp.
+        let style = Style::default().fg(Color::Rgb(100, 200, 50));
+        assert_eq!(fade(style, 0.9, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::LightGreen));
+        assert_eq!(fade(style, 0.5, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::Green));
+        let style = Style::default().fg(Color::Indexed(196));
+        assert_eq!(fade(style, 0.8, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::Red));
+
+        // Bold brightens the normal variant between it and the bright one.
+        let style = Style::default().fg(Color::LightCyan);
+        assert_eq!(fade(style, 0.9, ColorDepth::Ansi16, ColorSpace::Rgb).add_modifier, Modifier::empty());
+        assert_eq!(fade(style, 0.8, ColorDepth::Ansi16, ColorSpace::Rgb).add_modifier, Modifier::BOLD);
+        assert_eq!(fade(style, 0.8, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::Cyan));
+        assert_eq!(fade(style, 0.5, ColorDepth::Ansi16, ColorSpace::Rgb).add_modifier, Modifier::empty());
+
+        // Dim is added once the hue is gone.
+        let style = Style::default().fg(Color::Cyan);
+        assert_eq!(fade(style, 0.8, ColorDepth::Ansi16, ColorSpace::Rgb).add_modifier, Modifier::empty());
+        assert_eq!(fade(style, 0.5, ColorDepth::Ansi16, ColorSpace::Rgb).add_modifier, Modifier::DIM);
+        assert_eq!(fade(style, 0.5, ColorDepth::Ansi16, ColorSpace::Rgb).fg, Some(Color::DarkGray));
+    }
+
+    #[test]
+    fn test_ansi16_undimmed() {
+        // Cells no effect dims keep the style as given, modifiers included.
+        for style in [
+            Style::default().fg(Color::Cyan),
+            Style::default().fg(Color::LightCyan).add_modifier(Modifier::ITALIC),
+            Style::default().fg(Color::Rgb(100, 200, 50)),
+        ] {
+            assert_eq!(fade(style, 1.0, ColorDepth::Ansi16, ColorSpace::Rgb), style);
+            assert_eq!(gradient(style, 0.0, GRADIENT_STRENGTH, ColorDepth::Ansi16, ColorSpace::Rgb), style);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Rendering conformance checks.
+//!
+//! [`run`] renders a battery of mode × effect × size combinations into
+//! offscreen buffers and reports any panics or invalid glyphs. Downstream apps
+//! can call it from a test to verify the widget against their pinned ratatui
+//! version:
+//!
+//! ```rust
+//! let report = waveformchart::conformance::run();
+//! assert!(report.is_ok(), "{:#?}", report.failures);
+//! ```
+
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Widget},
+};
+
+use crate::color::{ColorDepth, ColorSpace, Colormap};
+use crate::glyphs::GlyphSupport;
+use crate::widget::{
+    Aggregate, Baseline, Direction, Downsample, FadeCurve, Fit, GradientDirection, Invalid, Marker, Orientation, Scale,
+    Stacking, WaveformMode, WaveformWidget,
+};
+
+/// Every rendering mode exercised by the checks.
+const MODES: [WaveformMode; 5] = [
+    WaveformMode::HighResBraille,
+    WaveformMode::UltraThinBlock,
+    WaveformMode::SolidBlock,
+    WaveformMode::Density,
+    WaveformMode::BrailleLine,
+];
+
+/// Effects, one per feature, applied on top of a plain widget; `all` and
+/// `all_vertical` combine them.
+const EFFECTS: [&str; 71] = [
+    "none", "fade", "fade_curve", "fade_full", "color_space_hsl", "color_space_oklch", "ansi256", "ansi16", "colormap",
+    "gradient", "gradient_stops", "gradient_direction", "gradient_strength", "value_colormap", "intensity", "range",
+    "dead_band", "autoscale", "autoscale_decay", "autoscale_percentile", "reflection", "equalize", "scale_db",
+    "scroll_phase", "bars", "align", "zoom", "zoom_out", "ascii", "vertical", "separators", "gap_style", "axis_labels",
+    "axis_format", "grid", "grid_dots", "underlay", "fit", "layers", "baseline", "baseline_style", "frame", "markers",
+    "reference_lines", "rtl", "flush_edge", "invalid_zero", "invalid_hold", "window", "offset", "show_last_value",
+    "downsample", "lttb", "stats", "center_gap", "soft_clip", "style_palette", "regions", "background", "under_curve",
+    "min_hold", "peak_hold", "clip_style", "burn_in", "overview", "edge_style", "peak_style", "cursor", "hover", "all",
+    "all_vertical",
+];
+
+/// Reference series for the underlay effect, including invalid values.
+const UNDERLAY: [f64; 6] = [0.0, 0.5, 1.0, 2.0, f64::NAN, 0.25];
+
+/// Area sizes (width, height), including degenerate ones.
+const SIZES: [(u16, u16); 7] = [(0, 0), (1, 1), (1, 2), (2, 3), (7, 5), (40, 1), (80, 24)];
+
+/// Outcome of a conformance run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConformanceReport {
+    /// Number of combinations rendered.
+    pub cases: usize,
+    /// Every problem found, in the order encountered.
+    pub failures: Vec<ConformanceFailure>,
+}
+
+impl ConformanceReport {
+    /// True if every combination rendered cleanly.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// A single failed combination.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConformanceFailure {
+    /// Human readable description of the combination, e.g. `Density/fade/7x5/ramp`.
+    pub case: String,
+    pub problem: Problem,
+}
+
+/// What went wrong while rendering a combination.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// Rendering panicked with the given message.
+    Panic(String),
+    /// A cell holds a symbol the widget should never produce.
+    InvalidGlyph { x: u16, y: u16, symbol: String },
+    /// A cell outside the render area was modified.
+    OutOfBounds { x: u16, y: u16 },
+}
+
+/// Renders every mode × effect × size × data combination and collects failures.
+pub fn run() -> ConformanceReport {
+    let datasets: [(&str, Vec<f64>); 6] = [
+        ("empty", Vec::new()),
+        ("zeros", vec![0.0; 100]),
+        ("ones", vec![1.0; 100]),
+        ("ramp", (0..100).map(|i| i as f64 / 99.0).collect()),
+        ("out_of_range", vec![-1.0, 2.0, 1e300, -1e300, 0.5]),
+        ("invalid", vec![f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 0.5]),
+    ];
+
+    let mut report = ConformanceReport { cases: 0, failures: Vec::new() };
+    for mode in MODES {
+        for effect in EFFECTS {
+            for (width, height) in SIZES {
+                for (name, data) in &datasets {
+                    for bordered in [false, true] {
+                        let case = format!("{mode:?}/{effect}/{width}x{height}/{name}{}", if bordered { "/block" } else { "" });
+                        report.cases += 1;
+                        if let Err(problem) = check(mode, effect, width, height, data, bordered) {
+                            report.failures.push(ConformanceFailure { case, problem });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Renders one combination inside a 1-cell margin and validates the result.
+fn check(mode: WaveformMode, effect: &str, width: u16, height: u16, data: &[f64], bordered: bool) -> Result<(), Problem> {
+    let area = Rect::new(1, 1, width, height);
+    let mut buf = Buffer::empty(Rect::new(0, 0, width + 2, height + 2));
+
+    let rendered = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut widget = configure(WaveformWidget::new(data, data).mode(mode), effect)
+            .top_style(Style::default().fg(Color::Green))
+            .bottom_style(Style::default().fg(Color::Reset));
+        if bordered {
+            widget = widget.block(Block::default().borders(Borders::ALL));
+        }
+        widget.render(area, &mut buf);
+    }));
+    if let Err(payload) = rendered {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        return Err(Problem::Panic(message));
+    }
+
+    for y in 0..buf.area.height {
+        for x in 0..buf.area.width {
+            let symbol = buf[(x, y)].symbol();
+            let inside = x >= area.left() && x < area.right() && y >= area.top() && y < area.bottom();
+            if !inside && symbol != " " {
+                return Err(Problem::OutOfBounds { x, y });
+            }
+            if !symbol.chars().all(is_valid_glyph) || symbol.chars().count() != 1 {
+                return Err(Problem::InvalidGlyph { x, y, symbol: symbol.to_string() });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn configure<'a>(widget: WaveformWidget<'a>, effect: &str) -> WaveformWidget<'a> {
+    match effect {
+        "fade" => widget.fade_effect(true),
+        "fade_curve" => widget.fade_effect(true).fade_start(0.3).fade_curve(FadeCurve::Pow(2.0)),
+        "fade_full" => widget.fade_effect(true).fade_start(1.0),
+        "color_space_hsl" => widget.fade_effect(true).color_space(ColorSpace::Hsl),
+        "color_space_oklch" => widget.gradient_effect(true).color_space(ColorSpace::Oklch),
+        "ansi256" => widget.fade_effect(true).color_depth(ColorDepth::Ansi256),
+        "ansi16" => widget.fade_effect(true).gradient_effect(true).color_depth(ColorDepth::Ansi16),
+        "colormap" => widget.colormap(Colormap::Turbo).color_depth(ColorDepth::Ansi16),
+        "gradient" => widget.gradient_effect(true),
+        "gradient_stops" => widget.gradient(&[(0.0, Color::Green), (0.7, Color::Yellow), (1.0, Color::Red)]),
+        "gradient_direction" => widget.gradient_effect(true).gradient_direction(GradientDirection::BrightPeaks),
+        "gradient_strength" => widget.gradient_effect(true).gradient_strength(1.0),
+        "value_colormap" => widget.value_colormap(Colormap::Viridis),
+        "intensity" => widget.bottom_intensity(0.4),
+        "range" => widget.top_range(0.2, 1.0),
+        "dead_band" => widget.top_dead_band(0.1),
+        "autoscale" => widget.autoscale(0.1),
+        "autoscale_decay" => widget.autoscale(0.1).autoscale_decay(0.95).offset(5),
+        "autoscale_percentile" => widget.autoscale_percentile(95.0, 0.1),
+        "reflection" => widget.reflection_effect(true),
+        "equalize" => widget.equalize(true),
+        "scale_db" => widget.scale(Scale::Db { floor: -40.0 }),
+        "scroll_phase" => widget.scroll_phase(0.75),
+        "bars" => widget.bar_width(2).bar_gap(1),
+        "align" => widget.bar_width(2).align(Alignment::Center),
+        "zoom" => widget.zoom(2.5),
+        "zoom_out" => widget.zoom(0.4),
+        "ascii" => widget.glyph_support(GlyphSupport::ASCII),
+        "vertical" => widget.orientation(Orientation::Vertical),
+        "separators" => widget.separators(3),
+        "gap_style" => widget.gap_style(Style::default()),
+        "axis_labels" => widget.axis_labels("%", "MB"),
+        "axis_format" => widget.axis_format(|v| format!("{:.0}%", v * 100.0)),
+        "grid" => widget.grid(0.2, Style::default()),
+        "grid_dots" => widget.grid(0.25, Style::default()).grid_dots(true).separators(3),
+        "underlay" => widget.top_underlay(&UNDERLAY).bottom_underlay(&UNDERLAY),
+        "fit" => widget.fit(Fit::Stretch),
+        "layers" => widget.top_layers(&[(&UNDERLAY[..], Style::default())]).stacking(Stacking::Percent),
+        "baseline" => widget.baseline(Baseline::Ratio(0.3)),
+        "baseline_style" => widget.baseline_style(Style::default()),
+        "frame" => widget.frame_style(Style::default()),
+        "markers" => widget.top_markers(&[Marker::new(0.5, "mid")]),
+        "reference_lines" => widget.reference_lines(&[(0.8, Style::default().fg(Color::Red), Some("80%"))]),
+        "rtl" => widget.direction(Direction::RightToLeft),
+        "flush_edge" => widget.flush_edge(true),
+        "invalid_zero" => widget.invalid(Invalid::Zero),
+        "invalid_hold" => widget.invalid(Invalid::HoldLast),
+        "window" => widget.window(1, 60),
+        "offset" => widget.offset(2),
+        "show_last_value" => widget.show_last_value(true),
+        "downsample" => widget.downsample(Downsample::MinMax),
+        "lttb" => widget.downsample(Downsample::Lttb),
+        "stats" => widget.stats(&[Aggregate::MIN, Aggregate::MAX, Aggregate::AVG]),
+        "center_gap" => widget.center_gap(1),
+        "soft_clip" => widget.soft_clip(0.7, 0.2, 4.0),
+        "style_palette" => widget.style_palette(true).fade_effect(true),
+        "regions" => widget.regions(&[(10..40, Style::default().bg(Color::Blue)), (0..usize::MAX, Style::default())]),
+        "background" => widget.background_style(Style::default().bg(Color::Black)),
+        "under_curve" => widget.under_curve_style(Style::default().bg(Color::DarkGray)),
+        "min_hold" => widget.top_min_hold(0.05, Style::default().fg(Color::Gray)),
+        "peak_hold" => widget
+            .top_peak_hold(0.9, Style::default().fg(Color::Gray))
+            .bottom_peak_hold(f64::NAN, Style::default()),
+        "clip_style" => widget.clip_style(Style::default().fg(Color::Red)),
+        "burn_in" => widget.burn_in_protection(true).frame_time(Duration::from_secs(900)),
+        "overview" => widget.overview(12, 4, Style::default().bg(Color::DarkGray)),
+        "edge_style" => widget.edge_style(Style::default().fg(Color::White)),
+        "peak_style" => widget.peak_style(Style::default().fg(Color::White)).fade_effect(true),
+        "cursor" => widget.cursor(Some(50)),
+        "hover" => widget.hover(Some((4, 2))),
+        "all" => all(widget),
+        "all_vertical" => all(widget).orientation(Orientation::Vertical),
+        _ => widget,
+    }
+}
+
+/// Every effect that can be combined, at once.
+fn all(widget: WaveformWidget) -> WaveformWidget {
+    widget
+        .fade_effect(true)
+        .gradient_effect(true)
+        .reflection_effect(true)
+        .equalize(true)
+        .scroll_phase(0.5)
+        .offset(3)
+        .center_gap(1)
+        .soft_clip(0.7, 0.2, 4.0)
+        .style_palette(true)
+        .regions(&[(10..40, Style::default().bg(Color::Blue)), (0..usize::MAX, Style::default())])
+        .background_style(Style::default().bg(Color::Black))
+        .under_curve_style(Style::default().bg(Color::DarkGray))
+        .top_min_hold(0.05, Style::default().fg(Color::Gray))
+        .top_peak_hold(0.9, Style::default().fg(Color::Gray))
+        .clip_style(Style::default().fg(Color::Red))
+        .burn_in_protection(true)
+        .frame_time(Duration::from_secs(900))
+        .downsample(Downsample::Lttb)
+        .gap_style(Style::default().fg(Color::DarkGray))
+        .overview(12, 4, Style::default().bg(Color::DarkGray))
+        .bar_width(3)
+        .bar_gap(2)
+        .separators(4)
+        .edge_style(Style::default().fg(Color::White))
+        .top_underlay(&UNDERLAY)
+        .bottom_underlay(&UNDERLAY)
+        .axis_labels("%", "MB")
+        .grid(0.2, Style::default())
+        .top_markers(&[Marker::new(0.5, "mid")])
+        .stats(&[Aggregate::MIN, Aggregate::MAX, Aggregate::AVG])
+        .show_last_value(true)
+        .cursor(Some(50))
+        .hover(Some((4, 2)))
+}
+
+/// Symbols the widget may legitimately produce.
+fn is_valid_glyph(c: char) -> bool {
+    c == ' '
+        || c.is_ascii_graphic()
+        || ('\u{2800}'..='\u{28FF}').contains(&c) // Braille patterns
+        || ('\u{2500}'..='\u{259F}').contains(&c) // Box drawing, block elements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conformance() {
+        let report = run();
+        assert!(report.cases > 0);
+        assert!(report.is_ok(), "{:#?}", report.failures);
+    }
+
+    #[test]
+    fn test_is_valid_glyph() {
+        assert!(is_valid_glyph('⡇'));
+        assert!(is_valid_glyph('▐'));
+        assert!(is_valid_glyph('░'));
+        assert!(!is_valid_glyph('\u{FFFD}'));
+        assert!(!is_valid_glyph('\n'));
+    }
+}
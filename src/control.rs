@@ -0,0 +1,151 @@
+//! Control-signal sources for live-performance tools (feature `control`).
+//!
+//! [`MidiCc`] decodes MIDI control change messages and [`OscListener`] receives
+//! OSC messages over UDP; both feed their values into a [`WaveformState`] as
+//! normalized samples, so control signals can be charted next to audio levels.
+//!
+//! No MIDI backend is bundled: pass the raw message bytes from the callback of
+//! whatever MIDI library the app already uses.
+
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+
+use crate::state::WaveformState;
+
+/// Largest OSC packet read from the socket.
+const MAX_PACKET: usize = 1536;
+
+/// A MIDI control change (CC) to follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MidiCc {
+    /// MIDI channel (0 - 15).
+    pub channel: u8,
+    /// Controller number (0 - 127).
+    pub controller: u8,
+}
+
+impl MidiCc {
+    /// Follows `controller` on `channel`.
+    pub fn new(channel: u8, controller: u8) -> Self {
+        Self { channel, controller }
+    }
+
+    /// Normalized value (0.0 - 1.0) of `message` if it is a control change
+    /// of this channel and controller.
+    pub fn decode(&self, message: &[u8]) -> Option<f64> {
+        match *message {
+            [status, controller, value, ..]
+                if status == 0xB0 | (self.channel & 0x0F) && controller == self.controller =>
+            {
+                Some((value & 0x7F) as f64 / 127.0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Pushes the value of a matching `message` into `state`.
+    /// Returns whether the message matched.
+    pub fn feed(&self, message: &[u8], state: &mut WaveformState) -> bool {
+        self.decode(message).map(|value| state.push(value)).is_some()
+    }
+}
+
+/// Decodes an OSC message into its address and first numeric argument
+/// (`f`, `i` or `d`). Bundles and messages without a number return `None`.
+pub fn decode_osc(packet: &[u8]) -> Option<(&str, f64)> {
+    let (address, rest) = osc_string(packet)?;
+    let (tags, args) = osc_string(rest)?;
+    if !address.starts_with('/') {
+        return None;
+    }
+    let tag = tags.strip_prefix(',')?.chars().next()?;
+    let value = match tag {
+        'f' => f32::from_be_bytes(args.get(..4)?.try_into().ok()?) as f64,
+        'i' => i32::from_be_bytes(args.get(..4)?.try_into().ok()?) as f64,
+        'd' => f64::from_be_bytes(args.get(..8)?.try_into().ok()?),
+        _ => return None,
+    };
+    Some((address, value))
+}
+
+/// Splits a null-terminated, 4-byte padded OSC string off `bytes`.
+fn osc_string(bytes: &[u8]) -> Option<(&str, &[u8])> {
+    let len = bytes.iter().position(|&b| b == 0)?;
+    let padded = (len + 4) & !3;
+    let text = std::str::from_utf8(&bytes[..len]).ok()?;
+    Some((text, bytes.get(padded..)?))
+}
+
+/// Receives OSC messages on a UDP socket and follows one address.
+#[derive(Debug)]
+pub struct OscListener {
+    socket: UdpSocket,
+    address: String,
+}
+
+impl OscListener {
+    /// Binds a non-blocking socket to `addr`, following the OSC `address`
+    /// (e.g. `/mixer/fader1`). Values are expected in 0.0 - 1.0.
+    pub fn bind(addr: impl ToSocketAddrs, address: &str) -> io::Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self { socket, address: address.to_string() })
+    }
+
+    /// Local address of the socket.
+    pub fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Drains the pending packets without blocking and pushes the values sent
+    /// to the followed address into `state`, clamped to 0.0 - 1.0.
+    /// Returns the number of samples pushed.
+    pub fn poll(&self, state: &mut WaveformState) -> io::Result<usize> {
+        let mut packet = [0; MAX_PACKET];
+        let mut pushed = 0;
+        loop {
+            let len = match self.socket.recv(&mut packet) {
+                Ok(len) => len,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(pushed),
+                Err(e) => return Err(e),
+            };
+            if let Some((address, value)) = decode_osc(&packet[..len])
+                && address == self.address
+            {
+                state.push(value.clamp(0.0, 1.0));
+                pushed += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_midi_cc() {
+        let cc = MidiCc::new(1, 7);
+        assert_eq!(cc.decode(&[0xB1, 7, 127]), Some(1.0));
+        assert_eq!(cc.decode(&[0xB0, 7, 127]), None);
+        assert_eq!(cc.decode(&[0x91, 7, 127]), None);
+
+        let mut state = WaveformState::new(4);
+        assert!(cc.feed(&[0xB1, 7, 0], &mut state));
+        assert_eq!(state.top(), [0.0]);
+    }
+
+    #[test]
+    fn test_decode_osc() {
+        let mut packet = b"/fader\0\0,f\0\0".to_vec();
+        packet.extend(0.5f32.to_be_bytes());
+        assert_eq!(decode_osc(&packet), Some(("/fader", 0.5)));
+
+        let mut packet = b"/a\0\0,i\0\0".to_vec();
+        packet.extend(3i32.to_be_bytes());
+        assert_eq!(decode_osc(&packet), Some(("/a", 3.0)));
+
+        assert_eq!(decode_osc(b"#bundle\0"), None);
+        assert_eq!(decode_osc(b"/a\0\0,s\0\0hi\0\0"), None);
+    }
+}
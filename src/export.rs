@@ -0,0 +1,206 @@
+//! Recording of rendered frames (feature `export`).
+//!
+//! [`CastRecorder`] renders a widget into an offscreen buffer once per frame
+//! and writes the frames as an [asciicast v2] recording, which can be played
+//! back with `asciinema play` or converted to an animated GIF with `agg`.
+//!
+//! [asciicast v2]: https://docs.asciinema.org/manual/asciicast/v2/
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::Widget,
+};
+
+/// Records successive frames of a chart as an asciicast.
+///
+/// # Example
+/// ```rust
+/// use waveformchart::{export::CastRecorder, WaveformState};
+///
+/// let mut state = WaveformState::new(40);
+/// let mut recorder = CastRecorder::new(40, 8);
+/// for frame in 0..10 {
+///     state.push((frame as f64 / 3.0).sin().abs());
+///     recorder.record(frame as f64 * 0.1, state.widget());
+/// }
+///
+/// let mut cast = Vec::new();
+/// recorder.write(&mut cast).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CastRecorder {
+    width: u16,
+    height: u16,
+    /// Time (seconds since the start) and terminal output of each frame.
+    frames: Vec<(f64, String)>,
+}
+
+impl CastRecorder {
+    /// Creates an empty recording of a `width` × `height` terminal.
+    pub fn new(width: u16, height: u16) -> Self {
+        Self { width, height, frames: Vec::new() }
+    }
+
+    /// Renders `widget` over the full terminal and records it as the frame
+    /// shown at `time` seconds. Times should be ascending.
+    pub fn record(&mut self, time: f64, widget: impl Widget) {
+        let area = Rect::new(0, 0, self.width, self.height);
+        let mut buf = Buffer::empty(area);
+        widget.render(area, &mut buf);
+        self.record_buffer(time, &buf);
+    }
+
+    /// Records an already rendered buffer as the frame shown at `time` seconds,
+    /// e.g. a whole app layout.
+    pub fn record_buffer(&mut self, time: f64, buf: &Buffer) {
+        self.frames.push((time, ansi_frame(buf)));
+    }
+
+    /// Number of frames recorded so far.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// True if no frame has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Writes the recording in the asciicast v2 format.
+    pub fn write(&self, mut out: impl Write) -> io::Result<()> {
+        writeln!(out, r#"{{"version": 2, "width": {}, "height": {}}}"#, self.width, self.height)?;
+        for (time, output) in &self.frames {
+            writeln!(out, r#"[{time:.6}, "o", "{}"]"#, json_escape(output))?;
+        }
+        Ok(())
+    }
+}
+
+/// Terminal output redrawing the whole buffer from the top-left corner.
+fn ansi_frame(buf: &Buffer) -> String {
+    let mut out = String::from("\x1b[H");
+    for y in buf.area.top()..buf.area.bottom() {
+        let mut current = None;
+        for x in buf.area.left()..buf.area.right() {
+            let cell = &buf[(x, y)];
+            let style = cell.style();
+            if current != Some(style) {
+                out.push_str(&sgr(style));
+                current = Some(style);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m");
+        if y + 1 < buf.area.bottom() {
+            out.push_str("\r\n");
+        }
+    }
+    out
+}
+
+/// Select Graphic Rendition sequence switching to `style` from any other style.
+fn sgr(style: Style) -> String {
+    let mut codes = vec!["0".to_string()];
+    const MODIFIERS: [(Modifier, &str); 8] = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    for (modifier, code) in MODIFIERS {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(style.fg.and_then(|color| color_code(color, 30)));
+    codes.extend(style.bg.and_then(|color| color_code(color, 40)));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// SGR parameters of `color` as a foreground (`base` 30) or background (40)
+/// color, `None` for the terminal default.
+fn color_code(color: Color, base: u8) -> Option<String> {
+    let named = |index: u8| Some(if index < 8 { base + index } else { base + 60 + index - 8 }.to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => named(0),
+        Color::Red => named(1),
+        Color::Green => named(2),
+        Color::Yellow => named(3),
+        Color::Blue => named(4),
+        Color::Magenta => named(5),
+        Color::Cyan => named(6),
+        Color::Gray => named(7),
+        Color::DarkGray => named(8),
+        Color::LightRed => named(9),
+        Color::LightGreen => named(10),
+        Color::LightYellow => named(11),
+        Color::LightBlue => named(12),
+        Color::LightMagenta => named(13),
+        Color::LightCyan => named(14),
+        Color::White => named(15),
+        Color::Indexed(i) => Some(format!("{};5;{i}", base + 8)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{r};{g};{b}", base + 8)),
+    }
+}
+
+/// Escapes `text` for use inside a JSON string.
+fn json_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::{WaveformMode, WaveformWidget};
+
+    #[test]
+    fn test_cast_recorder() {
+        let mut recorder = CastRecorder::new(2, 2);
+        let widget = WaveformWidget::single(&[1.0, 0.0])
+            .mode(WaveformMode::SolidBlock)
+            .top_style(Style::default().fg(Color::Rgb(1, 2, 3)));
+        recorder.record(0.5, widget);
+        assert_eq!(recorder.len(), 1);
+
+        let mut cast = Vec::new();
+        recorder.write(&mut cast).unwrap();
+        let cast = String::from_utf8(cast).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+        assert_eq!(lines[0], r#"{"version": 2, "width": 2, "height": 2}"#);
+        assert_eq!(
+            lines[1],
+            r#"[0.500000, "o", "\u001b[H\u001b[0;38;2;1;2;3m█\u001b[0m \u001b[0m\r\n\u001b[0;38;2;1;2;3m█\u001b[0m \u001b[0m"]"#
+        );
+    }
+
+    #[test]
+    fn test_color_code() {
+        assert_eq!(color_code(Color::Red, 30).as_deref(), Some("31"));
+        assert_eq!(color_code(Color::White, 40).as_deref(), Some("107"));
+        assert_eq!(color_code(Color::Indexed(200), 30).as_deref(), Some("38;5;200"));
+        assert_eq!(color_code(Color::Reset, 30), None);
+    }
+}
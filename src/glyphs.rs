@@ -0,0 +1,107 @@
+//! Glyph support of the target terminal.
+//!
+//! Braille patterns are missing from some fonts and older consoles (the
+//! Windows console host, the Linux virtual console), where they show up as
+//! boxes. [`GlyphSupport`] tells the widget which glyph sets are available so
+//! it can fall back to block or plain ASCII rendering instead.
+
+/// Glyph sets the target terminal can display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GlyphSupport {
+    /// Braille patterns (`⠀` - `⣿`), used by the Braille modes.
+    pub braille: bool,
+    /// Block elements and box drawing (`█`, `▌`, `░`, `│`, ...).
+    pub blocks: bool,
+}
+
+impl Default for GlyphSupport {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl GlyphSupport {
+    /// Every glyph is available.
+    pub const FULL: Self = Self { braille: true, blocks: true };
+
+    /// Block elements and box drawing, but no Braille.
+    pub const BLOCKS: Self = Self { braille: false, blocks: true };
+
+    /// Plain ASCII only.
+    pub const ASCII: Self = Self { braille: false, blocks: false };
+
+    /// Guesses the glyph support from the environment: non-UTF-8 locales and
+    /// `TERM=dumb` get ASCII, the Linux virtual console and the legacy Windows
+    /// console get blocks only. Anything else is assumed to support every glyph.
+    pub fn detect() -> Self {
+        Self::detect_from(cfg!(windows), |name| std::env::var(name).ok())
+    }
+
+    fn detect_from(windows: bool, var: impl Fn(&str) -> Option<String>) -> Self {
+        let term = var("TERM").unwrap_or_default();
+        if term == "dumb" {
+            return Self::ASCII;
+        }
+        if windows {
+            // Windows Terminal sets WT_SESSION; the legacy console host has no Braille.
+            return if var("WT_SESSION").is_some() { Self::FULL } else { Self::BLOCKS };
+        }
+        // The first locale variable set decides the character set.
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"].into_iter().find_map(|name| var(name).filter(|v| !v.is_empty()));
+        if let Some(locale) = locale {
+            let locale = locale.to_ascii_lowercase();
+            if !locale.contains("utf-8") && !locale.contains("utf8") {
+                return Self::ASCII;
+            }
+        }
+        if term == "linux" { Self::BLOCKS } else { Self::FULL }
+    }
+}
+
+/// Closest ASCII stand-in for a glyph the widget draws.
+pub(crate) fn ascii(glyph: char) -> char {
+    match glyph {
+        '█' | '▓' | '▋' | '▊' | '▉' => '#',
+        '▒' => '+',
+        '░' => '.',
+        '▌' | '▐' | '▏' | '▎' | '▍' | '▕' | '│' | '┃' | '╎' => '|',
+        '▀' | '▄' | '─' | '╌' | '┈' => '-',
+        '━' => '=',
+        '┊' => ':',
+        '┌' | '┐' | '└' | '┘' | '┼' | '├' | '┤' | '┬' | '┴' => '+',
+        // Frame arrowheads.
+        '⡱' => '>',
+        '⢎' => '<',
+        '\u{2800}' => ' ',
+        '\u{2801}'..='\u{28FF}' => '.',
+        c if c.is_ascii() => c,
+        '\u{2500}'..='\u{257F}' => '+',
+        _ => '?',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+        assert_eq!(GlyphSupport::detect_from(false, env(&[("TERM", "xterm"), ("LANG", "en_US.UTF-8")])), GlyphSupport::FULL);
+        assert_eq!(GlyphSupport::detect_from(false, env(&[("TERM", "linux"), ("LANG", "C.UTF-8")])), GlyphSupport::BLOCKS);
+        assert_eq!(GlyphSupport::detect_from(false, env(&[("TERM", "xterm"), ("LC_ALL", "C")])), GlyphSupport::ASCII);
+        assert_eq!(GlyphSupport::detect_from(true, env(&[])), GlyphSupport::BLOCKS);
+        assert_eq!(GlyphSupport::detect_from(true, env(&[("WT_SESSION", "1")])), GlyphSupport::FULL);
+    }
+
+    #[test]
+    fn test_ascii() {
+        assert_eq!(ascii('█'), '#');
+        assert_eq!(ascii('⣿'), '.');
+        assert_eq!(ascii('┊'), ':');
+        assert_eq!(ascii('E'), 'E');
+    }
+}
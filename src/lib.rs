@@ -1,3 +1,21 @@
+pub mod color;
+pub mod conformance;
+#[cfg(feature = "control")]
+pub mod control;
+#[cfg(feature = "export")]
+pub mod export;
+pub mod glyphs;
+pub mod state;
+pub mod theme;
+pub mod transform;
 pub mod widget;
 
-pub use widget::{WaveformWidget, WaveformMode};
+pub use color::{ColorDepth, ColorSpace, Colormap};
+pub use glyphs::GlyphSupport;
+pub use state::{FrozenWaveform, ScrollState, View, ViewEvent, WaveformState};
+pub use theme::WaveformTheme;
+pub use widget::{
+    Aggregate, Baseline, Direction, Downsample, FadeCurve, Fit, GradientDirection, Hit, Invalid, Label, Marker,
+    Orientation, RenderedColumn, RenderedSample, Sample, SampleSource, Scale, Side, SloBand, Stacking, WaveformMode,
+    WaveformWidget, Zone,
+};
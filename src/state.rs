@@ -0,0 +1,1128 @@
+//! Live sample history for streaming charts.
+//!
+//! [`WaveformState`] keeps the most recent samples of both halves and hands
+//! them to a [`WaveformWidget`] each frame. [`WaveformState::freeze`] copies the
+//! current history into a [`FrozenWaveform`] that can be rendered later, while
+//! the live state keeps updating.
+//!
+//! View settings (scales, zoom, markers) can be changed through recorded
+//! [`ViewEvent`]s, which [`WaveformState::undo`] and [`WaveformState::redo`]
+//! step through, e.g. to back out of an accidental zoom.
+//!
+//! [`ScrollState`] is the state of the widget's [`StatefulWidget`] impl: it
+//! pans back through history and keeps a paused view in place while new
+//! samples arrive.
+//!
+//! [`StatefulWidget`]: ratatui::widgets::StatefulWidget
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use ratatui::style::{Color, Style};
+
+use crate::widget::{Marker, WaveformWidget};
+
+/// Bounded history of the top and bottom series.
+#[derive(Debug, Clone, Default)]
+pub struct WaveformState {
+    top: History,
+    bottom: History,
+    capacity: usize,
+    /// Longest expected silence between samples before gaps are inserted.
+    heartbeat: Option<Duration>,
+    /// Time of the last sample or inserted gap.
+    last_activity: Option<Instant>,
+    /// Style of the gap markers after the last real sample.
+    stale_style: Option<Style>,
+    /// Recorded view changes; the first `applied` ones are in effect.
+    events: Vec<ViewEvent>,
+    applied: usize,
+    /// View resulting from the applied events.
+    view: View,
+    /// Headroom and per-sample decay of the autoscaled maxima.
+    autoscale: Option<(f64, f64)>,
+    /// Hold time and fall per second of the peak-hold lines.
+    peak_hold: Option<(Duration, f64)>,
+    /// Held peak of the top and bottom series and when it was reached.
+    held_peaks: [Option<(f64, Instant)>; 2],
+    /// Style of the peak-hold lines, `None` for the default.
+    peak_hold_style: Option<Style>,
+    /// Buffer cell under the mouse.
+    hover: Option<(u16, u16)>,
+    /// Anchor and moving end of the selection in the top and bottom series,
+    /// as sample numbers (indices plus the samples dropped before them), so it
+    /// stays on the same samples of each.
+    selection: Option<[(usize, usize); 2]>,
+    /// Whether the selection is still being dragged.
+    selecting: bool,
+    /// Tint of the selected columns, `None` for the default.
+    selection_style: Option<Style>,
+}
+
+impl WaveformState {
+    /// Creates an empty state keeping at most `capacity` samples per series.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, ..Self::default() }
+    }
+
+    /// Maximum number of samples kept per series.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the number of samples kept per series, e.g. to follow the
+    /// terminal width. Shrinking drops the oldest samples.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.top.truncate(capacity);
+        self.bottom.truncate(capacity);
+    }
+
+    /// Enables the watchdog: whenever [`tick`](Self::tick) finds no sample
+    /// arrived for `timeout`, a gap is inserted, so a frozen source shows as
+    /// gap markers instead of looking like a flatlined metric.
+    pub fn heartbeat(mut self, timeout: Duration) -> Self {
+        self.heartbeat = Some(timeout);
+        self
+    }
+
+    /// Draws the trailing gap markers, after the last real sample, in this
+    /// style to flag the source as stale (default: same as the other gaps).
+    pub fn stale_style(mut self, style: Style) -> Self {
+        self.stale_style = Some(style);
+        self
+    }
+
+    /// Tints the selected columns in this style (default: a dark blue-gray
+    /// background). See [`begin_selection`](Self::begin_selection).
+    pub fn selection_style(mut self, style: Style) -> Self {
+        self.selection_style = Some(style);
+        self
+    }
+
+    /// Scales the widget to the peak of the samples in view plus `headroom`
+    /// (e.g. `0.1` for 10%). Once a spike scrolls out of view, it still
+    /// counts, shrunk by `decay` (e.g. `0.99`) per sample of distance, so the
+    /// chart doesn't jump. See [`WaveformWidget::autoscale_decay`].
+    pub fn autoscale(mut self, headroom: f64, decay: f64) -> Self {
+        self.autoscale = Some((headroom.max(0.0), decay.clamp(0.0, 1.0)));
+        self
+    }
+
+    /// Tracks a peak-hold line per series, like an audio meter: it jumps to
+    /// every new maximum, stays there for `hold`, then falls by `decay` (in
+    /// data units) per second until a sample reaches it again. Drawn by
+    /// [`widget`](Self::widget).
+    pub fn peak_hold(mut self, hold: Duration, decay: f64) -> Self {
+        self.peak_hold = Some((hold, decay.max(0.0)));
+        self
+    }
+
+    /// Draws the [`peak_hold`](Self::peak_hold) lines in this style (default: gray).
+    pub fn peak_hold_style(mut self, style: Style) -> Self {
+        self.peak_hold_style = Some(style);
+        self
+    }
+
+    /// Peak-hold levels of the top and bottom series at `now`, `None` before
+    /// the first sample or without [`peak_hold`](Self::peak_hold).
+    pub fn held_peaks(&self, now: Instant) -> (Option<f64>, Option<f64>) {
+        let level = |held: Option<(f64, Instant)>| {
+            let (hold, decay) = self.peak_hold?;
+            let (peak, at) = held?;
+            let falling = now.saturating_duration_since(at).saturating_sub(hold);
+            Some(peak - decay * falling.as_secs_f64())
+        };
+        (level(self.held_peaks[0]), level(self.held_peaks[1]))
+    }
+
+    /// Sets the buffer cell under the mouse, e.g. from a crossterm
+    /// `MouseEventKind::Moved` event, or clears it with `None`. The
+    /// [`widget`](Self::widget) highlights the column there and reports its
+    /// sample with [`WaveformWidget::hovered`].
+    pub fn set_hover(&mut self, position: Option<(u16, u16)>) {
+        self.hover = position;
+    }
+
+    /// Buffer cell under the mouse, if any.
+    pub fn hover(&self) -> Option<(u16, u16)> {
+        self.hover
+    }
+
+    /// Starts selecting at data `index`, e.g. on a mouse press, replacing any
+    /// previous selection. Map the pressed cell to an index with
+    /// [`WaveformWidget::hit_test`]. Indices are positions in [`top`](Self::top)
+    /// and [`bottom`](Self::bottom); the selection then stays on the same
+    /// samples as older ones are dropped at capacity.
+    pub fn begin_selection(&mut self, index: usize) {
+        let [top, bottom] = [&self.top, &self.bottom].map(|history| history.dropped + index);
+        self.selection = Some([(top, top), (bottom, bottom)]);
+        self.selecting = true;
+    }
+
+    /// Moves the end of the selection being dragged to `index`, e.g. on a
+    /// mouse drag. Does nothing once the selection has ended.
+    pub fn update_selection(&mut self, index: usize) {
+        let numbers = [&self.top, &self.bottom].map(|history| history.dropped + index);
+        if let Some(selection) = self.selection.as_mut().filter(|_| self.selecting) {
+            selection.iter_mut().zip(numbers).for_each(|((_, end), number)| *end = number);
+        }
+    }
+
+    /// Finishes the selection being dragged, e.g. on a mouse release.
+    pub fn end_selection(&mut self) {
+        self.selecting = false;
+    }
+
+    /// Removes the selection.
+    pub fn clear_selection(&mut self) {
+        self.selection = None;
+        self.selecting = false;
+    }
+
+    /// Data indices covered by the selection, both ends included; `None`
+    /// without a selection or once all its samples have been dropped.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        self.selection_in(0, &self.top)
+    }
+
+    /// Selected samples of the top series, empty without a selection.
+    pub fn selected(&self) -> &[f64] {
+        selected(self.top(), self.selection_in(0, &self.top))
+    }
+
+    /// Selected samples of the bottom series, empty without a selection.
+    pub fn selected_bottom(&self) -> &[f64] {
+        selected(self.bottom(), self.selection_in(1, &self.bottom))
+    }
+
+    /// The selection of `series` (0 for top, 1 for bottom) as indices into
+    /// its `history`, skipping dropped samples.
+    fn selection_in(&self, series: usize, history: &History) -> Option<Range<usize>> {
+        let (anchor, end) = self.selection?[series];
+        let index = |number: usize| number.saturating_sub(history.dropped);
+        Some(index(anchor.min(end))..index(anchor.max(end) + 1)).filter(|range| !range.is_empty())
+    }
+
+    /// Autoscaled maxima of the top and bottom halves with the whole history
+    /// in view, if autoscaling. Narrower charts scale to the samples they show.
+    pub fn scales(&self) -> Option<(f64, f64)> {
+        let (headroom, _) = self.autoscale?;
+        let scale = |data: &[f64]| {
+            let peak = data.iter().copied().filter(|v| v.is_finite()).fold(0.0, f64::max);
+            peak.max(f64::EPSILON) * (1.0 + headroom)
+        };
+        Some((scale(self.top()), scale(self.bottom())))
+    }
+
+    /// Appends a sample to the top series, dropping the oldest one when full.
+    pub fn push(&mut self, value: f64) {
+        self.push_at(value, Instant::now());
+    }
+
+    /// Appends a sample to the bottom series, dropping the oldest one when full.
+    pub fn push_bottom(&mut self, value: f64) {
+        self.push_bottom_at(value, Instant::now());
+    }
+
+    /// Like [`push`](Self::push), with the arrival time given explicitly
+    /// instead of read from the clock, for reproducible heartbeats.
+    pub fn push_at(&mut self, value: f64, now: Instant) {
+        self.hold_peak(0, value, now);
+        self.top.push(value, self.capacity);
+        self.last_activity = Some(now);
+    }
+
+    /// Like [`push_bottom`](Self::push_bottom), with an explicit arrival time.
+    pub fn push_bottom_at(&mut self, value: f64, now: Instant) {
+        self.hold_peak(1, value, now);
+        self.bottom.push(value, self.capacity);
+        self.last_activity = Some(now);
+    }
+
+    fn hold_peak(&mut self, series: usize, value: f64, now: Instant) {
+        if self.peak_hold.is_none() || !value.is_finite() {
+            return;
+        }
+        let (top, bottom) = self.held_peaks(now);
+        let held = if series == 0 { top } else { bottom };
+        if held.is_none_or(|level| value >= level) {
+            self.held_peaks[series] = Some((value, now));
+        }
+    }
+
+    /// Checks the heartbeat at `now` (usually `Instant::now()` once per frame)
+    /// and inserts one gap (a NaN sample) into each series in use for every
+    /// full timeout elapsed without a sample. Returns the number of gaps.
+    pub fn tick(&mut self, now: Instant) -> usize {
+        let (Some(timeout), Some(mut last)) = (self.heartbeat, self.last_activity) else {
+            return 0;
+        };
+        if timeout.is_zero() {
+            return 0;
+        }
+        // Gaps beyond the capacity would be dropped again right away.
+        let max_gaps = self.capacity.max(1);
+        let mut gaps = 0;
+        while now.saturating_duration_since(last) >= timeout && gaps < max_gaps {
+            for history in [&mut self.top, &mut self.bottom] {
+                if !history.as_slice().is_empty() {
+                    history.push(f64::NAN, self.capacity);
+                }
+            }
+            last += timeout;
+            gaps += 1;
+        }
+        self.last_activity = Some(if gaps == max_gaps { now } else { last });
+        gaps
+    }
+
+    /// Retained samples of the top series, oldest first.
+    pub fn top(&self) -> &[f64] {
+        self.top.as_slice()
+    }
+
+    /// Retained samples of the bottom series, oldest first.
+    pub fn bottom(&self) -> &[f64] {
+        self.bottom.as_slice()
+    }
+
+    /// The newest `width` samples of the top series (fewer while filling up),
+    /// oldest first: what fits in a chart `width` columns wide.
+    pub fn visible(&self, width: usize) -> &[f64] {
+        newest(self.top(), width)
+    }
+
+    /// The newest `width` samples of the bottom series, oldest first.
+    pub fn visible_bottom(&self, width: usize) -> &[f64] {
+        newest(self.bottom(), width)
+    }
+
+    /// Applies a view change and records it for [`undo`](Self::undo).
+    /// Changes undone before are discarded.
+    pub fn record(&mut self, event: ViewEvent) {
+        self.events.truncate(self.applied);
+        self.view.apply(&event);
+        self.events.push(event);
+        self.applied += 1;
+    }
+
+    /// Reverts the last applied view change. Returns false if there is none.
+    pub fn undo(&mut self) -> bool {
+        if self.applied == 0 {
+            return false;
+        }
+        self.applied -= 1;
+        self.view = View::default();
+        for event in &self.events[..self.applied] {
+            self.view.apply(event);
+        }
+        true
+    }
+
+    /// Re-applies the last undone view change. Returns false if there is none.
+    pub fn redo(&mut self) -> bool {
+        let Some(event) = self.events.get(self.applied) else {
+            return false;
+        };
+        self.view.apply(event);
+        self.applied += 1;
+        true
+    }
+
+    /// The view changes in effect, oldest first.
+    pub fn events(&self) -> &[ViewEvent] {
+        &self.events[..self.applied]
+    }
+
+    /// The view settings resulting from the recorded changes.
+    pub fn view(&self) -> &View {
+        &self.view
+    }
+
+    /// Borrows the current history as a widget, ready to be configured and rendered.
+    /// The recorded view settings are applied; with a heartbeat, gaps are drawn as markers.
+    pub fn widget(&self) -> WaveformWidget<'_> {
+        self.widget_at(Instant::now())
+    }
+
+    /// Like [`widget`](Self::widget), with the peak-hold lines placed as of
+    /// `now` instead of the clock, for reproducible frames.
+    pub fn widget_at(&self, now: Instant) -> WaveformWidget<'_> {
+        let first = (self.top.dropped, self.bottom.dropped);
+        let widget = configure(WaveformWidget::new(self.top(), self.bottom()), &self.view, self.autoscale, first)
+            .top_markers(&self.view.markers())
+            .hover(self.hover);
+        let widget = match self.selection() {
+            Some(range) => {
+                let style = self.selection_style.unwrap_or(SELECTION_STYLE);
+                widget.regions(&[(range, style)])
+            }
+            None => widget,
+        };
+        let (top_peak, bottom_peak) = self.held_peaks(now);
+        let style = self.peak_hold_style.unwrap_or(PEAK_HOLD_STYLE);
+        let widget = match top_peak {
+            Some(level) => widget.top_peak_hold(level, style),
+            None => widget,
+        };
+        let widget = match bottom_peak {
+            Some(level) => widget.bottom_peak_hold(level, style),
+            None => widget,
+        };
+        if self.heartbeat.is_none() {
+            return widget;
+        }
+        let widget = widget.gap_style(Style::default().fg(Color::DarkGray));
+        match self.stale_style {
+            Some(style) => widget.stale_style(style),
+            None => widget,
+        }
+    }
+
+    /// Copies the current history into an owned snapshot that is unaffected
+    /// by later pushes, e.g. for a "pinned charts" panel. The view settings,
+    /// autoscaling and sample numbering are kept, so it renders as the live
+    /// [`widget`](Self::widget) did.
+    pub fn freeze(&self) -> FrozenWaveform {
+        FrozenWaveform {
+            top: self.top().to_vec(),
+            bottom: self.bottom().to_vec(),
+            view: self.view.clone(),
+            autoscale: self.autoscale,
+            first: (self.top.dropped, self.bottom.dropped),
+        }
+    }
+}
+
+/// Applies the view settings but the markers, autoscaling and sample numbering
+/// shared by [`WaveformState::widget`] and [`FrozenWaveform::widget`].
+fn configure<'a>(
+    widget: WaveformWidget<'a>,
+    view: &View,
+    autoscale: Option<(f64, f64)>,
+    (top_first, bottom_first): (usize, usize),
+) -> WaveformWidget<'a> {
+    let widget = widget
+        .top_max(view.top_max)
+        .bottom_max(view.bottom_max)
+        .zoom(view.zoom)
+        .first_sample(top_first, bottom_first);
+    match autoscale {
+        Some((headroom, decay)) => widget.autoscale(headroom).autoscale_decay(decay),
+        None => widget,
+    }
+}
+
+impl View {
+    /// The markers as widget markers, borrowing their labels.
+    fn markers(&self) -> Vec<Marker<'_>> {
+        self.markers.iter().map(|(value, label)| Marker::new(*value, label)).collect()
+    }
+}
+
+/// A recordable change of the view settings, see [`WaveformState::record`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViewEvent {
+    /// Sets the scale of the top half.
+    TopMax(f64),
+    /// Sets the scale of the bottom half.
+    BottomMax(f64),
+    /// Sets the horizontal zoom, see [`WaveformWidget::zoom`].
+    Zoom(f64),
+    /// Adds a labeled marker to the top half.
+    AddMarker { value: f64, label: String },
+    /// Removes every marker.
+    ClearMarkers,
+}
+
+/// View settings of a [`WaveformState`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct View {
+    /// Scale of the top half.
+    pub top_max: f64,
+    /// Scale of the bottom half.
+    pub bottom_max: f64,
+    /// Horizontal zoom.
+    pub zoom: f64,
+    /// Markers of the top half: value and label.
+    pub markers: Vec<(f64, String)>,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self { top_max: 1.0, bottom_max: 1.0, zoom: 1.0, markers: Vec::new() }
+    }
+}
+
+impl View {
+    fn apply(&mut self, event: &ViewEvent) {
+        match event {
+            ViewEvent::TopMax(max) => self.top_max = *max,
+            ViewEvent::BottomMax(max) => self.bottom_max = *max,
+            ViewEvent::Zoom(zoom) => self.zoom = *zoom,
+            ViewEvent::AddMarker { value, label } => self.markers.push((*value, label.clone())),
+            ViewEvent::ClearMarkers => self.markers.clear(),
+        }
+    }
+}
+
+/// Immutable, owned snapshot of a [`WaveformState`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FrozenWaveform {
+    top: Vec<f64>,
+    bottom: Vec<f64>,
+    view: View,
+    /// Headroom and per-sample decay of the autoscaled maxima.
+    autoscale: Option<(f64, f64)>,
+    /// Sample numbers of the first top and bottom samples.
+    first: (usize, usize),
+}
+
+impl FrozenWaveform {
+    /// Samples of the top series, oldest first.
+    pub fn top(&self) -> &[f64] {
+        &self.top
+    }
+
+    /// Samples of the bottom series, oldest first.
+    pub fn bottom(&self) -> &[f64] {
+        &self.bottom
+    }
+
+    /// View settings of the state when it was frozen.
+    pub fn view(&self) -> &View {
+        &self.view
+    }
+
+    /// Borrows the snapshot as a widget, ready to be configured and rendered.
+    /// The view settings of the state it was frozen from are applied.
+    pub fn widget(&self) -> WaveformWidget<'_> {
+        configure(WaveformWidget::new(&self.top, &self.bottom), &self.view, self.autoscale, self.first)
+            .top_markers(&self.view.markers())
+    }
+
+    /// Turns the snapshot into a widget that owns the samples. The view
+    /// settings are applied but the markers, whose labels the widget borrows.
+    pub fn into_widget(self) -> WaveformWidget<'static> {
+        configure(WaveformWidget::owned(self.top, self.bottom), &self.view, self.autoscale, self.first)
+    }
+}
+
+/// The samples of `data` within `range`, clamped to its length.
+fn selected(data: &[f64], range: Option<Range<usize>>) -> &[f64] {
+    let range = range.unwrap_or_default();
+    &data[range.start.min(data.len())..range.end.min(data.len())]
+}
+
+/// Default tint of the selected columns.
+const SELECTION_STYLE: Style = Style::new().bg(Color::Rgb(40, 40, 60));
+
+/// Default style of the peak-hold lines.
+const PEAK_HOLD_STYLE: Style = Style::new().fg(Color::Gray);
+
+/// The last `count` samples of `data`.
+fn newest(data: &[f64], count: usize) -> &[f64] {
+    &data[data.len().saturating_sub(count)..]
+}
+
+/// Horizontal scroll position of a chart rendered as a
+/// [`StatefulWidget`](ratatui::widgets::StatefulWidget).
+///
+/// While following, the chart shows the newest samples. Scrolling back pauses
+/// it: the view then stays on the same samples as the data keeps growing,
+/// until it is scrolled forward to the tail again or [`follow`](Self::follow)
+/// is called. The position is tracked by sample number: growing data holds
+/// still as is, and a bounded history such as a [`WaveformState`] at capacity
+/// does too when its widget numbers its samples with
+/// [`first_sample`](crate::WaveformWidget::first_sample).
+///
+/// It also keeps the column heights of the last frame for the
+/// [`hysteresis`](crate::WaveformWidget::hysteresis) option, and a zoom
+/// applied on top of the widget's, see [`zoom_in_at`](Self::zoom_in_at).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrollState {
+    /// Number of newest samples scrolled out of view.
+    offset: usize,
+    following: bool,
+    /// Data length at the last render, `None` before the first one.
+    len: Option<usize>,
+    /// Number just past the newest sample at the last render.
+    end: usize,
+    /// Quantized heights of the top and bottom samples drawn in the last
+    /// frame, by sample number (see [`first_sample`](crate::WaveformWidget::first_sample)).
+    pub(crate) levels: [HashMap<usize, u16>; 2],
+    /// Sample number of the first top and bottom samples drawn in the last
+    /// frame, with the bits of the dead band's held value entering them.
+    pub(crate) dead_bands: [Option<(usize, u64)>; 2],
+    /// Data index of the crosshair, see [`set_cursor`](Self::set_cursor).
+    pub(crate) cursor: Option<usize>,
+    /// Zoom steps applied on top of the widget's zoom, positive to zoom in.
+    zoom_steps: i32,
+    /// Time axis layout of the last render, `None` before the first one.
+    pub(crate) viewport: Option<Viewport>,
+}
+
+/// Where the samples of the last render were drawn along the time axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Viewport {
+    /// Buffer column (row for vertical charts) of the newest sample.
+    pub(crate) newest: u16,
+    /// Whether older samples lie at higher coordinates.
+    pub(crate) reversed: bool,
+    /// Cells per bar slot, gap included.
+    pub(crate) pitch: usize,
+    /// Bar slots across the chart.
+    pub(crate) capacity: usize,
+    /// Samples in view.
+    pub(crate) span: usize,
+}
+
+/// Zoom factor of one [`ScrollState::zoom_in_at`] step.
+const ZOOM_STEP: f64 = 1.25;
+
+/// Most zoom steps either way (about 1000x), bounding zooms before the first render.
+const MAX_ZOOM_STEPS: i32 = 31;
+
+impl Default for ScrollState {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            following: true,
+            len: None,
+            end: 0,
+            levels: Default::default(),
+            dead_bands: [None; 2],
+            cursor: None,
+            zoom_steps: 0,
+            viewport: None,
+        }
+    }
+}
+
+impl ScrollState {
+    /// Creates a state following the newest samples.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of newest samples scrolled out of view (0 while following).
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// True while the view follows the newest samples.
+    pub fn is_following(&self) -> bool {
+        self.following
+    }
+
+    /// Jumps to the newest samples and keeps following them.
+    pub fn follow(&mut self) {
+        self.following = true;
+        self.offset = 0;
+    }
+
+    /// Holds the view on the samples currently in view.
+    pub fn pause(&mut self) {
+        self.following = false;
+    }
+
+    /// Pans `samples` back in history and pauses the view.
+    pub fn scroll_back(&mut self, samples: usize) {
+        self.offset = self.offset.saturating_add(samples);
+        self.following = false;
+    }
+
+    /// Pans `samples` towards the newest ones. Reaching them resumes following.
+    pub fn scroll_forward(&mut self, samples: usize) {
+        self.offset = self.offset.saturating_sub(samples);
+        if self.offset == 0 {
+            self.following = true;
+        }
+    }
+
+    /// Places the crosshair on data index `index`, or removes it with `None`.
+    /// See [`WaveformWidget::cursor`](crate::WaveformWidget::cursor).
+    pub fn set_cursor(&mut self, index: Option<usize>) {
+        self.cursor = index;
+    }
+
+    /// Data index of the crosshair, if any.
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    /// Zooms in one step (by 1.25x), e.g. on a mouse wheel event, keeping the
+    /// sample at buffer column `x` (the row for vertical charts) in place by
+    /// adjusting the offset. Does nothing once a single sample fills the chart.
+    pub fn zoom_in_at(&mut self, x: u16) {
+        if self.viewport.is_none_or(|viewport| viewport.span > 1) {
+            self.zoom_at(x, ZOOM_STEP);
+        }
+    }
+
+    /// Zooms out one step, keeping the sample at buffer column `x` (the row
+    /// for vertical charts) in place as far as the data allows. Does nothing
+    /// once all the data is in view.
+    pub fn zoom_out_at(&mut self, x: u16) {
+        let len = self.len.unwrap_or(0);
+        if self.viewport.is_none_or(|viewport| viewport.span < len) {
+            self.zoom_at(x, 1.0 / ZOOM_STEP);
+        }
+    }
+
+    /// Goes back to the widget's own zoom.
+    pub fn reset_zoom(&mut self) {
+        self.zoom_steps = 0;
+    }
+
+    /// Zoom applied on top of the widget's [`zoom`](crate::WaveformWidget::zoom).
+    pub fn zoom_factor(&self) -> f64 {
+        ZOOM_STEP.powi(self.zoom_steps)
+    }
+
+    fn zoom_at(&mut self, x: u16, factor: f64) {
+        let step = if factor > 1.0 { 1 } else { -1 };
+        self.zoom_steps = (self.zoom_steps + step).clamp(-MAX_ZOOM_STEPS, MAX_ZOOM_STEPS);
+        let Some(viewport) = self.viewport else {
+            return;
+        };
+        // Samples between the newest edge and the one under `x`, which shrinks
+        // by `factor`; the offset makes up the difference.
+        let cells = if viewport.reversed { x.saturating_sub(viewport.newest) } else { viewport.newest.saturating_sub(x) };
+        let slots = cells as usize / viewport.pitch.max(1);
+        let samples = (slots * viewport.span) as f64 / viewport.capacity.max(1) as f64;
+        let offset = (self.offset as f64 + samples * (1.0 - 1.0 / factor)).round().max(0.0) as usize;
+        if offset > self.offset {
+            self.scroll_back(offset - self.offset);
+        } else {
+            self.scroll_forward(self.offset - offset);
+        }
+    }
+
+    /// Updates the position for data of `len` samples ending just before
+    /// sample number `end`, of which `capacity` fit in view, and returns the
+    /// offset to render with.
+    pub(crate) fn update(&mut self, len: usize, capacity: usize, end: usize) -> usize {
+        if self.following {
+            self.offset = 0;
+        } else if self.len.is_some() {
+            // Keep the same samples in view while new ones arrive.
+            self.offset = self.offset.saturating_add(end.saturating_sub(self.end));
+        }
+        self.offset = self.offset.min(len.saturating_sub(capacity));
+        self.len = Some(len);
+        self.end = end;
+        self.offset
+    }
+}
+
+/// Contiguous sample buffer. Dropped samples are only compacted away once they
+/// outnumber the capacity, so pushes stay amortized O(1).
+#[derive(Debug, Clone, Default)]
+struct History {
+    data: Vec<f64>,
+    start: usize,
+    /// Samples dropped from the front so far, i.e. the number of the first one kept.
+    dropped: usize,
+}
+
+impl History {
+    fn push(&mut self, value: f64, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        self.data.push(value);
+        if self.data.len() - self.start > capacity {
+            self.start += 1;
+            self.dropped += 1;
+        }
+        if self.start >= capacity {
+            self.data.drain(..self.start);
+            self.start = 0;
+        }
+    }
+
+    /// Keeps the newest `capacity` samples only.
+    fn truncate(&mut self, capacity: usize) {
+        let excess = self.as_slice().len().saturating_sub(capacity);
+        self.data.drain(..self.start + excess);
+        self.start = 0;
+        self.dropped += excess;
+    }
+
+    fn as_slice(&self) -> &[f64] {
+        &self.data[self.start..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_capacity() {
+        let mut state = WaveformState::new(3);
+        for v in 0..10 {
+            state.push(v as f64);
+        }
+        state.push_bottom(1.0);
+        assert_eq!(state.top(), [7.0, 8.0, 9.0]);
+        assert_eq!(state.bottom(), [1.0]);
+    }
+
+    #[test]
+    fn test_visible_and_set_capacity() {
+        let mut state = WaveformState::new(5);
+        for v in 0..5 {
+            state.push(v as f64);
+        }
+        assert_eq!(state.visible(2), [3.0, 4.0]);
+        assert_eq!(state.visible(10).len(), 5);
+        assert!(state.visible_bottom(2).is_empty());
+
+        state.set_capacity(3);
+        assert_eq!(state.capacity(), 3);
+        assert_eq!(state.top(), [2.0, 3.0, 4.0]);
+        state.push(5.0);
+        assert_eq!(state.top(), [3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_scroll_state() {
+        let mut scroll = ScrollState::new();
+        assert_eq!(scroll.update(10, 4, 10), 0);
+
+        scroll.scroll_back(2);
+        assert!(!scroll.is_following());
+        assert_eq!(scroll.update(10, 4, 10), 2);
+        // New samples push the paused view further back.
+        assert_eq!(scroll.update(13, 4, 13), 5);
+        // Can't scroll past the oldest full window.
+        scroll.scroll_back(100);
+        assert_eq!(scroll.update(13, 4, 13), 9);
+
+        scroll.scroll_forward(9);
+        assert!(scroll.is_following());
+        assert_eq!(scroll.update(20, 4, 20), 0);
+    }
+
+    #[test]
+    fn test_scroll_bounded_history() {
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+
+        let mut state = WaveformState::new(8);
+        let area = Rect::new(0, 0, 2, 2);
+        let mut scroll = ScrollState::new();
+        let newest_in_view = |state: &WaveformState, scroll: &mut ScrollState| {
+            let widget = state.widget().mode(crate::WaveformMode::SolidBlock);
+            widget.render(area, &mut Buffer::empty(area), scroll);
+            let offset = scroll.offset();
+            let shown = state.widget().mode(crate::WaveformMode::SolidBlock).offset(offset);
+            shown.hit_test(area, (1, 0)).map(|hit| hit.value)
+        };
+        for v in 0..8 {
+            state.push(v as f64);
+        }
+        newest_in_view(&state, &mut scroll);
+        scroll.scroll_back(3);
+        assert_eq!(newest_in_view(&state, &mut scroll), Some(4.0));
+
+        // The history is full: each push drops a sample, yet the view holds still.
+        state.push(8.0);
+        state.push(9.0);
+        assert_eq!(state.top().len(), 8);
+        assert_eq!(newest_in_view(&state, &mut scroll), Some(4.0));
+    }
+
+    #[test]
+    fn test_view_undo_redo() {
+        let mut state = WaveformState::new(4);
+        state.record(ViewEvent::Zoom(2.0));
+        state.record(ViewEvent::AddMarker { value: 0.5, label: "limit".to_string() });
+        state.record(ViewEvent::ClearMarkers);
+        assert!(state.view().markers.is_empty());
+
+        assert!(state.undo());
+        assert_eq!(state.view().markers, [(0.5, "limit".to_string())]);
+        assert!(state.undo());
+        assert!(state.undo());
+        assert!(!state.undo());
+        assert_eq!(*state.view(), View::default());
+
+        assert!(state.redo());
+        assert_eq!(state.view().zoom, 2.0);
+        // A new change discards the undone ones.
+        state.record(ViewEvent::TopMax(4.0));
+        assert!(!state.redo());
+        assert_eq!(state.events(), [ViewEvent::Zoom(2.0), ViewEvent::TopMax(4.0)]);
+    }
+
+    #[test]
+    fn test_autoscale_decay() {
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        let mut state = WaveformState::new(4).autoscale(0.5, 0.5);
+        state.push(4.0);
+        assert_eq!(state.scales(), Some((6.0, f64::EPSILON * 1.5)));
+        assert_eq!(WaveformState::new(4).scales(), None);
+
+        // A 2 column chart shows the two 0.2 samples; the spike just before
+        // them still counts for 4 * 0.5, so they stay small.
+        let area = Rect::new(0, 0, 2, 2);
+        let render = |state: &WaveformState| {
+            let mut buf = Buffer::empty(area);
+            state.widget().render(area, &mut buf);
+            (0..2).map(|x| buf[(x, 0)].symbol().to_string()).collect::<String>()
+        };
+        state.push(0.2);
+        state.push(0.2);
+        assert_eq!(render(&state), "  ");
+        let mut state = WaveformState::new(4).autoscale(0.5, 0.0);
+        [4.0, 0.2, 0.2].into_iter().for_each(|v| state.push(v));
+        assert_eq!(render(&state), "⡆⡆");
+    }
+
+    #[test]
+    fn test_peak_hold() {
+        let mut state = WaveformState::new(8).peak_hold(Duration::from_secs(1), 0.5);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        state.push_at(1.0, start);
+        state.push_at(0.2, at(100));
+
+        // Held for a second, then falling by 0.5 per second.
+        assert_eq!(state.held_peaks(at(900)), (Some(1.0), None));
+        assert_eq!(state.held_peaks(at(1500)), (Some(0.75), None));
+        // A sample above the falling line takes over.
+        state.push_at(0.8, at(1500));
+        assert_eq!(state.held_peaks(at(2000)).0, Some(0.8));
+        assert_eq!(WaveformState::new(8).held_peaks(start), (None, None));
+    }
+
+    #[test]
+    fn test_peak_hold_render() {
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        let start = Instant::now();
+        let mut state = WaveformState::new(8)
+            .peak_hold(Duration::ZERO, 1.0)
+            .peak_hold_style(Style::default().fg(Color::Red));
+        state.push_at(1.0, start);
+        state.push_at(0.0, start);
+
+        // The line falls with the time given, not the clock.
+        let area = Rect::new(0, 0, 2, 8);
+        let row = |now| {
+            let mut buf = Buffer::empty(area);
+            state.widget_at(now).render(area, &mut buf);
+            (0..8).find(|&y| (0..2).any(|x| buf[(x, y)].symbol() == "─" && buf[(x, y)].fg == Color::Red))
+        };
+        assert_eq!(row(start), Some(0));
+        assert_eq!(row(start + Duration::from_millis(500)), Some(1));
+    }
+
+    #[test]
+    fn test_hover() {
+        use crate::{Hit, Side};
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        let mut state = WaveformState::new(4);
+        for v in [0.25, 0.5, 0.75, 1.0] {
+            state.push(v);
+        }
+        let area = Rect::new(0, 0, 2, 4);
+        state.set_hover(Some((1, 0)));
+        let widget = state.widget().mode(crate::WaveformMode::SolidBlock);
+        assert_eq!(widget.hovered(area), Some(Hit { series: Side::Top, index: 3, value: 1.0 }));
+
+        let mut buf = Buffer::empty(area);
+        widget.render(area, &mut buf);
+        // The whole hovered column is highlighted, the others are not.
+        assert!((0..4).all(|y| buf[(1, y)].bg == Color::DarkGray));
+        assert!((0..4).all(|y| buf[(0, y)].bg == Color::Reset));
+
+        state.set_hover(None);
+        assert_eq!(state.widget().hovered(area), None);
+    }
+
+    #[test]
+    fn test_selection() {
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        let mut state = WaveformState::new(8);
+        for v in 0..6 {
+            state.push(v as f64);
+        }
+        assert!(state.selected().is_empty());
+
+        // Dragging leftwards from the anchor.
+        state.begin_selection(4);
+        state.update_selection(2);
+        assert_eq!(state.selection(), Some(2..5));
+        state.end_selection();
+        state.update_selection(0);
+        assert_eq!(state.selected(), [2.0, 3.0, 4.0]);
+        assert!(state.selected_bottom().is_empty());
+
+        // Rendered as a region.
+        let area = Rect::new(0, 0, 6, 2);
+        let mut buf = Buffer::empty(area);
+        state.widget().render(area, &mut buf);
+        let tinted: Vec<bool> = (0..6).map(|x| buf[(x, 0)].bg == Color::Rgb(40, 40, 60)).collect();
+        assert_eq!(tinted, [false, false, true, true, true, false]);
+
+        // Clamped to the retained samples.
+        state.begin_selection(5);
+        state.update_selection(10);
+        assert_eq!(state.selected(), [5.0]);
+        state.clear_selection();
+        assert_eq!(state.selection(), None);
+    }
+
+    #[test]
+    fn test_selection_dropped_samples() {
+        let mut state = WaveformState::new(4);
+        for v in 0..4 {
+            state.push(v as f64);
+        }
+        state.begin_selection(1);
+        state.update_selection(2);
+        state.end_selection();
+
+        // Old samples are dropped: the selection moves with its samples.
+        state.push(4.0);
+        assert_eq!(state.selection(), Some(0..2));
+        assert_eq!(state.selected(), [1.0, 2.0]);
+        state.push(5.0);
+        assert_eq!(state.selected(), [2.0]);
+        // Gone once all of them are.
+        state.push(6.0);
+        assert_eq!(state.selection(), None);
+        assert!(state.selected().is_empty());
+    }
+
+    #[test]
+    fn test_selection_per_series() {
+        let mut state = WaveformState::new(4);
+        for v in 0..6 {
+            state.push(v as f64);
+        }
+        for v in 0..3 {
+            state.push_bottom(10.0 + v as f64);
+        }
+        state.begin_selection(1);
+        state.update_selection(2);
+        state.end_selection();
+
+        // Only the top series dropped samples; each keeps its own.
+        assert_eq!(state.selected(), [3.0, 4.0]);
+        assert_eq!(state.selected_bottom(), [11.0, 12.0]);
+        state.push(6.0);
+        state.push_bottom(13.0);
+        assert_eq!(state.selected(), [3.0, 4.0]);
+        assert_eq!(state.selected_bottom(), [11.0, 12.0]);
+    }
+
+    #[test]
+    fn test_zoom_at() {
+        use crate::WaveformWidget;
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+
+        let data: Vec<f64> = (0..100).map(|v| v as f64).collect();
+        let area = Rect::new(0, 0, 10, 2);
+        let widget = || WaveformWidget::single(&data).mode(crate::WaveformMode::SolidBlock);
+        let render = |scroll: &mut ScrollState| widget().render(area, &mut Buffer::empty(area), scroll);
+        let index_at = |scroll: &ScrollState, x| widget().hit_test_with(scroll, area, (x, 1)).map(|hit| hit.index);
+
+        let mut scroll = ScrollState::new();
+        render(&mut scroll);
+        assert_eq!(index_at(&scroll, 0), Some(90));
+
+        // The sample under the oldest column stays there.
+        scroll.zoom_in_at(0);
+        render(&mut scroll);
+        assert_eq!(scroll.zoom_factor(), 1.25);
+        assert_eq!(index_at(&scroll, 0), Some(90));
+        assert!(!scroll.is_following());
+
+        scroll.zoom_out_at(0);
+        render(&mut scroll);
+        assert_eq!((scroll.zoom_factor(), scroll.offset()), (1.0, 0));
+        assert!(scroll.is_following());
+
+        // Zooming at the newest edge keeps following it.
+        scroll.zoom_in_at(9);
+        assert_eq!(scroll.offset(), 0);
+        assert!(scroll.is_following());
+        scroll.reset_zoom();
+
+        // Nothing left to zoom out to once all the data is in view.
+        let mut scroll = ScrollState::new();
+        WaveformWidget::single(&data[..5]).render(area, &mut Buffer::empty(area), &mut scroll);
+        scroll.zoom_out_at(0);
+        assert_eq!(scroll.zoom_factor(), 1.0);
+
+        // Before the first render, zooming is bounded all the same.
+        let mut scroll = ScrollState::new();
+        (0..100).for_each(|_| scroll.zoom_in_at(0));
+        assert_eq!(scroll.zoom_factor(), ZOOM_STEP.powi(MAX_ZOOM_STEPS));
+        (0..200).for_each(|_| scroll.zoom_out_at(0));
+        assert_eq!(scroll.zoom_factor(), ZOOM_STEP.powi(-MAX_ZOOM_STEPS));
+    }
+
+    #[test]
+    fn test_heartbeat() {
+        let mut state = WaveformState::new(8).heartbeat(Duration::from_millis(100));
+        let start = Instant::now();
+        state.push_at(0.5, start);
+
+        assert_eq!(state.tick(start + Duration::from_millis(50)), 0);
+        assert_eq!(state.tick(start + Duration::from_millis(250)), 2);
+        assert_eq!(state.tick(start + Duration::from_millis(299)), 0);
+        assert_eq!(state.tick(start + Duration::from_millis(300)), 1);
+        assert_eq!(state.top().len(), 4);
+        assert!(state.top()[1..].iter().all(|v| v.is_nan()));
+        // The unused bottom series stays empty.
+        assert!(state.bottom().is_empty());
+    }
+
+    #[test]
+    fn test_freeze() {
+        let mut state = WaveformState::new(2);
+        state.push(0.5);
+        let frozen = state.freeze();
+        state.push(1.0);
+        state.push(1.0);
+
+        assert_eq!(frozen.top(), [0.5]);
+        assert_eq!(state.top(), [1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_freeze_view() {
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        let mut state = WaveformState::new(6).autoscale(0.1, 0.9);
+        for v in 0..9 {
+            state.push(v as f64 % 4.0);
+            state.push_bottom(v as f64);
+        }
+        state.record(ViewEvent::Zoom(2.0));
+        state.record(ViewEvent::AddMarker { value: 2.0, label: "hi".into() });
+
+        // The snapshot renders as the live widget it was frozen from.
+        let area = Rect::new(0, 0, 8, 6);
+        let render = |widget: WaveformWidget| {
+            let mut buf = Buffer::empty(area);
+            widget.render(area, &mut buf);
+            buf
+        };
+        let frozen = state.freeze();
+        let live = render(state.widget());
+        assert_eq!(render(frozen.widget()), live);
+        assert_eq!(frozen.view(), state.view());
+        assert_ne!(render(WaveformWidget::new(frozen.top(), frozen.bottom())), live);
+    }
+}
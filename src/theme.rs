@@ -0,0 +1,183 @@
+//! Reusable visual themes.
+//!
+//! A [`WaveformTheme`] bundles the look of a chart (styles, gradient stops,
+//! fade, mode and glyphs) so it can be applied in one call with
+//! [`WaveformWidget::theme`](crate::WaveformWidget::theme):
+//!
+//! ```rust
+//! use waveformchart::{WaveformTheme, WaveformWidget};
+//!
+//! let data = [0.2, 0.5, 0.9];
+//! let widget = WaveformWidget::new(&data, &data).theme(&WaveformTheme::sunset());
+//! ```
+//!
+//! With the `serde` feature, themes can be loaded from configuration files;
+//! missing fields keep their defaults.
+
+use ratatui::style::{Color, Style};
+
+use crate::color::GRADIENT_STRENGTH;
+use crate::glyphs::GlyphSupport;
+use crate::widget::{FadeCurve, GradientDirection, WaveformMode};
+
+/// Look of a chart, applied with [`WaveformWidget::theme`](crate::WaveformWidget::theme).
+///
+/// The default theme matches the widget's own defaults.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct WaveformTheme {
+    /// Style of the top half.
+    pub top_style: Style,
+    /// Style of the bottom half.
+    pub bottom_style: Style,
+    /// Gradient color stops by height, empty for none.
+    pub gradient: Vec<(f64, Color)>,
+    /// Whether the vertical gradient dimming is enabled.
+    pub gradient_effect: bool,
+    /// End of the columns the vertical gradient keeps brightest.
+    pub gradient_direction: GradientDirection,
+    /// Dimming of the vertical gradient at its dim end (0.0 - 1.0).
+    pub gradient_strength: f64,
+    /// Whether the horizontal fade is enabled.
+    pub fade_effect: bool,
+    /// Fraction of the width from the newest edge kept at full brightness.
+    pub fade_start: f64,
+    /// How brightness falls off towards the oldest column.
+    pub fade_curve: FadeCurve,
+    /// Rendering mode.
+    pub mode: WaveformMode,
+    /// Glyphs the target terminal can display.
+    pub glyph_support: GlyphSupport,
+}
+
+impl Default for WaveformTheme {
+    fn default() -> Self {
+        Self {
+            top_style: Style::default(),
+            bottom_style: Style::default(),
+            gradient: Vec::new(),
+            gradient_effect: false,
+            gradient_direction: GradientDirection::BrightCenter,
+            gradient_strength: GRADIENT_STRENGTH,
+            fade_effect: false,
+            fade_start: 0.5,
+            fade_curve: FadeCurve::Linear,
+            mode: WaveformMode::HighResBraille,
+            glyph_support: GlyphSupport::FULL,
+        }
+    }
+}
+
+impl WaveformTheme {
+    /// Names of the built-in presets, see [`preset`](Self::preset).
+    pub const PRESETS: [&'static str; 3] = ["matrix", "sunset", "monochrome"];
+
+    /// The built-in preset called `name`, if any.
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "matrix" => Some(Self::matrix()),
+            "sunset" => Some(Self::sunset()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// Green Braille trails fading into the dark.
+    pub fn matrix() -> Self {
+        Self {
+            top_style: Style::default().fg(Color::Rgb(0, 255, 70)),
+            bottom_style: Style::default().fg(Color::Rgb(0, 160, 40)),
+            fade_effect: true,
+            fade_start: 0.3,
+            fade_curve: FadeCurve::Pow(2.0),
+            ..Self::default()
+        }
+    }
+
+    /// Solid columns shading from gold at the baseline to violet at the peaks.
+    pub fn sunset() -> Self {
+        Self {
+            top_style: Style::default().fg(Color::Rgb(255, 170, 60)),
+            bottom_style: Style::default().fg(Color::Rgb(255, 110, 90)),
+            gradient: vec![(0.0, Color::Rgb(255, 200, 60)), (0.5, Color::Rgb(255, 90, 90)), (1.0, Color::Rgb(140, 60, 200))],
+            fade_effect: true,
+            mode: WaveformMode::SolidBlock,
+            ..Self::default()
+        }
+    }
+
+    /// Shades of gray with a height gradient, for any terminal palette.
+    pub fn monochrome() -> Self {
+        Self {
+            top_style: Style::default().fg(Color::White),
+            bottom_style: Style::default().fg(Color::Gray),
+            gradient_effect: true,
+            fade_effect: true,
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WaveformWidget;
+    use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+    #[test]
+    fn test_presets() {
+        for name in WaveformTheme::PRESETS {
+            assert!(WaveformTheme::preset(name).is_some(), "{name}");
+        }
+        assert!(WaveformTheme::preset("unknown").is_none());
+
+        // A theme is applied like the equivalent builder calls.
+        let area = Rect::new(0, 0, 4, 4);
+        let data = [0.2, 0.6, 1.0, 0.4];
+        let render = |widget: WaveformWidget| {
+            let mut buf = Buffer::empty(area);
+            widget.render(area, &mut buf);
+            buf
+        };
+        let theme = WaveformTheme::sunset();
+        let themed = render(WaveformWidget::new(&data, &data).theme(&theme));
+        let built = render(
+            WaveformWidget::new(&data, &data)
+                .top_style(theme.top_style)
+                .bottom_style(theme.bottom_style)
+                .gradient(&theme.gradient)
+                .fade_effect(true)
+                .mode(WaveformMode::SolidBlock),
+        );
+        assert_eq!(themed, built);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize() {
+        let theme: WaveformTheme = toml::from_str(
+            r##"
+            mode = "SolidBlock"
+            fade_effect = true
+            gradient = [[0.0, "green"], [1.0, "#ff0000"]]
+
+            [top_style]
+            fg = "cyan"
+            add_modifier = ""
+            sub_modifier = ""
+            "##,
+        )
+        .unwrap();
+        assert_eq!(theme.mode, WaveformMode::SolidBlock);
+        assert!(theme.fade_effect);
+        assert_eq!(theme.gradient, [(0.0, Color::Green), (1.0, Color::Rgb(255, 0, 0))]);
+        assert_eq!(theme.top_style, Style::default().fg(Color::Cyan));
+        // Missing fields keep their defaults.
+        assert_eq!(theme.fade_start, 0.5);
+        assert_eq!(theme.bottom_style, Style::default());
+
+        let round_trip: WaveformTheme = toml::from_str(&toml::to_string(&WaveformTheme::matrix()).unwrap()).unwrap();
+        assert_eq!(round_trip.top_style, WaveformTheme::matrix().top_style);
+    }
+}
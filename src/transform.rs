@@ -0,0 +1,155 @@
+//! Smoothing filters for noisy data.
+//!
+//! Each filter is fed one sample at a time, so it can smooth a live stream
+//! before it reaches a [`WaveformState`](crate::WaveformState):
+//!
+//! ```rust
+//! use waveformchart::transform::{Ema, Filter};
+//! use waveformchart::WaveformState;
+//!
+//! let mut state = WaveformState::new(500);
+//! let mut ema = Ema::new(0.2);
+//! for cpu in [0.2, 0.9, 0.3, 0.4] {
+//!     state.push(ema.apply(cpu));
+//! }
+//! ```
+//!
+//! or applied to a whole slice with [`Filter::run`]. NaN samples (gaps) and
+//! infinities are passed through without affecting the filter.
+
+use std::collections::VecDeque;
+
+/// A stateful, sample-by-sample smoothing filter.
+pub trait Filter {
+    /// Feeds `value` to the filter and returns the smoothed value.
+    fn apply(&mut self, value: f64) -> f64;
+
+    /// Smooths a whole slice, continuing from the current state.
+    fn run(&mut self, data: &[f64]) -> Vec<f64> {
+        data.iter().map(|&v| self.apply(v)).collect()
+    }
+}
+
+/// Simple moving average of the last `window` samples.
+#[derive(Debug, Clone, Default)]
+pub struct Sma {
+    window: usize,
+    samples: VecDeque<f64>,
+    sum: f64,
+}
+
+impl Sma {
+    /// Averages the last `window` samples (at least 1).
+    pub fn new(window: usize) -> Self {
+        Self { window: window.max(1), samples: VecDeque::new(), sum: 0.0 }
+    }
+}
+
+impl Filter for Sma {
+    fn apply(&mut self, value: f64) -> f64 {
+        if !value.is_finite() {
+            return value;
+        }
+        self.samples.push_back(value);
+        self.sum += value;
+        if self.samples.len() > self.window {
+            self.sum -= self.samples.pop_front().unwrap_or_default();
+        }
+        self.sum / self.samples.len() as f64
+    }
+}
+
+/// Exponential moving average: each sample moves the output `alpha` of the
+/// way towards it.
+#[derive(Debug, Clone, Default)]
+pub struct Ema {
+    alpha: f64,
+    value: Option<f64>,
+}
+
+impl Ema {
+    /// Smooths with `alpha` in 0.0 - 1.0: lower is smoother, 1.0 is no smoothing.
+    pub fn new(alpha: f64) -> Self {
+        Self { alpha: alpha.clamp(0.0, 1.0), value: None }
+    }
+}
+
+impl Filter for Ema {
+    fn apply(&mut self, value: f64) -> f64 {
+        if !value.is_finite() {
+            return value;
+        }
+        let smoothed = self.value.map_or(value, |previous| previous + self.alpha * (value - previous));
+        self.value = Some(smoothed);
+        smoothed
+    }
+}
+
+/// Median of the last `window` samples, removing isolated spikes while
+/// keeping steps sharp.
+#[derive(Debug, Clone, Default)]
+pub struct Median {
+    window: usize,
+    samples: VecDeque<f64>,
+}
+
+impl Median {
+    /// Takes the median of the last `window` samples (at least 1).
+    pub fn new(window: usize) -> Self {
+        Self { window: window.max(1), samples: VecDeque::new() }
+    }
+}
+
+impl Filter for Median {
+    fn apply(&mut self, value: f64) -> f64 {
+        if !value.is_finite() {
+            return value;
+        }
+        self.samples.push_back(value);
+        if self.samples.len() > self.window {
+            self.samples.pop_front();
+        }
+        let mut sorted: Vec<f64> = self.samples.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+        let mid = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) { (sorted[mid - 1] + sorted[mid]) / 2.0 } else { sorted[mid] }
+    }
+}
+
+/// Simple moving average of `data` over `window` samples.
+pub fn sma(data: &[f64], window: usize) -> Vec<f64> {
+    Sma::new(window).run(data)
+}
+
+/// Exponential moving average of `data`, see [`Ema`].
+pub fn ema(data: &[f64], alpha: f64) -> Vec<f64> {
+    Ema::new(alpha).run(data)
+}
+
+/// Moving median of `data` over `window` samples.
+pub fn median(data: &[f64], window: usize) -> Vec<f64> {
+    Median::new(window).run(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters() {
+        assert_eq!(sma(&[1.0, 3.0, 5.0, 7.0], 2), [1.0, 2.0, 4.0, 6.0]);
+        assert_eq!(ema(&[0.0, 1.0, 1.0], 0.5), [0.0, 0.5, 0.75]);
+        assert_eq!(median(&[1.0, 9.0, 2.0, 3.0], 3), [1.0, 5.0, 2.0, 3.0]);
+
+        // Gaps pass through without resetting the filter.
+        let smoothed = sma(&[1.0, f64::NAN, 3.0], 2);
+        assert!(smoothed[1].is_nan());
+        assert_eq!(smoothed[2], 2.0);
+
+        // So do infinities, which would otherwise poison the running state.
+        let data = [1.0, f64::INFINITY, 3.0, f64::NEG_INFINITY, 5.0];
+        assert_eq!(sma(&data, 2), [1.0, f64::INFINITY, 2.0, f64::NEG_INFINITY, 4.0]);
+        assert_eq!(ema(&data, 0.5), [1.0, f64::INFINITY, 2.0, f64::NEG_INFINITY, 3.5]);
+        assert_eq!(median(&data, 2), [1.0, f64::INFINITY, 2.0, f64::NEG_INFINITY, 4.0]);
+    }
+}
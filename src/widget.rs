@@ -1,12 +1,31 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::color::{self, ColorDepth, ColorSpace, Colormap};
+use crate::glyphs::{self, GlyphSupport};
+use crate::state::{ScrollState, Viewport};
+use crate::theme::WaveformTheme;
 use ratatui::{
-    buffer::Buffer,
-    layout::Rect,
+    buffer::{Buffer, Cell},
+    layout::{Alignment, Position, Rect},
     style::{Color, Style},
-    widgets::{Block, Widget},
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Scrollbar, ScrollbarOrientation, ScrollbarState, Widget},
 };
 
+/// Brightness of the mirrored copy drawn by the reflection effect.
+const REFLECTION_BRIGHTNESS: f64 = 0.3;
+
+/// Most samples before the view read by the dead band and the autoscale
+/// decay, so long histories don't cost more per frame.
+const LOOK_BACK: usize = 4096;
+
 /// Defines the rendering style of the waveform columns.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WaveformMode {
     /// High vertical resolution (4x) using only the left column of Braille dots.
     /// Visually thin dots, smooth peaks/valleys.
@@ -18,6 +37,367 @@ pub enum WaveformMode {
     /// Visually solid blocks, "steppy" vertical changes.
     /// Uses: ▌
     UltraThinBlock,
+
+    /// Standard vertical resolution (1x) using the Full Block character, so each
+    /// column fills the entire cell width for a chunky, solid waveform.
+    /// Uses: █
+    SolidBlock,
+
+    /// Constant-height columns whose shade encodes the value (intensity strip).
+    /// Fills every row of its half, so it suits compact one- or two-row meters.
+    /// Uses: ░ ▒ ▓ █
+    Density,
+
+    /// Thin connected trace (4x vertical resolution) instead of filled columns.
+    /// Consecutive samples are joined by vertical runs of dots, like an EEG trace.
+    /// Uses the left column of Braille dots.
+    BrailleLine,
+}
+
+/// Direction in which time flows through the chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Orientation {
+    /// Time flows left to right; amplitude extends up and down from a horizontal center line.
+    #[default]
+    Horizontal,
+
+    /// Time flows top to bottom (newest sample at the bottom); amplitude extends
+    /// right (top series) and left (bottom series) from a vertical center axis.
+    /// Suits narrow sidebars. Braille cells only have two dots across, so the
+    /// amplitude resolution is 2x per cell instead of 4x; solid blocks fill
+    /// their tip cell in eighths instead. Markers, stats and last value labels
+    /// stay horizontal; axis labels are not drawn.
+    Vertical,
+}
+
+/// Direction in which history flows across the chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    /// Newest sample on the right; history flows to the left.
+    #[default]
+    LeftToRight,
+
+    /// Newest sample on the left; history flows to the right, like a radar
+    /// or scope trace.
+    RightToLeft,
+}
+
+/// Which end of a column the vertical gradient keeps brightest, see
+/// [`WaveformWidget::gradient_direction`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GradientDirection {
+    /// Brightest at the baseline, dimming towards the peaks.
+    #[default]
+    BrightCenter,
+
+    /// Brightest at the peaks, dimming towards the baseline, like a level meter.
+    BrightPeaks,
+}
+
+/// Scaling of stacked layers, see [`WaveformWidget::top_layers`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Stacking {
+    /// Layers add up on the half's scale (`top_max` / `bottom_max`).
+    #[default]
+    Absolute,
+
+    /// Each column is scaled to the sum of its layers, so the stack always
+    /// fills the half (100% stacked area), showing composition over time.
+    Percent,
+}
+
+/// Mapping of values to column heights, see [`WaveformWidget::scale`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Scale {
+    /// Height proportional to the value.
+    #[default]
+    Linear,
+
+    /// Decibels relative to the max: the max is full height and `floor`
+    /// (negative, e.g. `-60.0`) and anything quieter are empty.
+    Db { floor: f64 },
+
+    /// Logarithmic over `decades` powers of ten below the max, e.g. `3.0`
+    /// spreads 0.001 - 1.0 (of the max) over the height.
+    Log { decades: f64 },
+}
+
+impl Scale {
+    /// Height (0.0 - 1.0 within range) of a value normalized to the max.
+    fn apply(self, val: f64) -> f64 {
+        let decades = match self {
+            Scale::Linear => return val,
+            Scale::Db { floor } => -floor / 20.0,
+            Scale::Log { decades } => decades,
+        };
+        if val <= 0.0 || decades <= 0.0 {
+            return if val.is_nan() { val } else { 0.0 };
+        }
+        (1.0 + val.log10() / decades).max(0.0)
+    }
+}
+
+/// How brightness falls off across the fading part of the chart, see
+/// [`WaveformWidget::fade_curve`].
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FadeCurve {
+    /// Brightness grows evenly from the oldest column to the fade start.
+    #[default]
+    Linear,
+
+    /// Linear progress raised to a power: above 1.0 history dims faster,
+    /// below 1.0 it stays bright longer.
+    Pow(f64),
+
+    /// Maps the progress (0.0 at the oldest column, 1.0 at the fade start)
+    /// to a brightness (clamped to 0.0 - 1.0). Not serializable.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(fn(f64) -> f64),
+}
+
+impl FadeCurve {
+    fn apply(self, t: f64) -> f64 {
+        let brightness = match self {
+            FadeCurve::Linear => t,
+            FadeCurve::Pow(exponent) => t.powf(exponent),
+            FadeCurve::Custom(curve) => curve(t),
+        };
+        brightness.clamp(0.0, 1.0)
+    }
+}
+
+/// Handling of invalid (NaN or infinite) samples, see [`WaveformWidget::invalid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Invalid {
+    /// Leaves the column empty, like a gap in the data.
+    #[default]
+    Skip,
+
+    /// Draws the sample as zero.
+    Zero,
+
+    /// Repeats the previous valid sample in view (an empty column if there is none).
+    HoldLast,
+}
+
+/// How buckets of samples are reduced to one bar when downsampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Downsample {
+    /// Bar height is the bucket maximum, so peaks never alias away. Line
+    /// mode traces the full min..max range of each bucket.
+    MinMax,
+    /// Mean of the bucket.
+    Average,
+    /// Root mean square of the bucket, tracking signal energy (audio loudness).
+    Rms,
+    /// Largest-Triangle-Three-Buckets: picks the one real sample per bucket
+    /// that best preserves the visual shape of the data.
+    Lttb,
+}
+
+/// How data shorter than the chart width is spread over it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fit {
+    /// One sample per bar; the rest of the width stays blank (see `align`).
+    #[default]
+    Natural,
+
+    /// Resamples the data with linear interpolation to fill the full width,
+    /// e.g. for an overview of a fixed-length audio clip.
+    Stretch,
+
+    /// Like `Stretch`, but repeats the nearest sample instead of interpolating,
+    /// keeping steps between samples sharp.
+    StretchNearest,
+}
+
+/// Text generated by the widget itself, passed to the
+/// [`localize`](WaveformWidget::localize) hook.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum Label {
+    /// Tag shown while histogram equalization is active (default `EQ`).
+    Equalized,
+    /// Name of the [`Aggregate::MIN`] stat (default `min`).
+    Min,
+    /// Name of the [`Aggregate::MAX`] stat (default `max`).
+    Max,
+    /// Name of the [`Aggregate::AVG`] stat (default `avg`).
+    Avg,
+    /// A number in an axis label, stat, last-value readout or cursor callout,
+    /// before its unit (default: compact, e.g. `42` or `1.5k`).
+    Value(f64),
+}
+
+impl Label {
+    /// Built-in English text of the label.
+    pub fn default_text(self) -> String {
+        match self {
+            Label::Equalized => "EQ".to_string(),
+            Label::Min => "min".to_string(),
+            Label::Max => "max".to_string(),
+            Label::Avg => "avg".to_string(),
+            Label::Value(value) => format_value(value),
+        }
+    }
+}
+
+/// Where the line separating the two halves sits.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Baseline {
+    /// Split at the vertical center: the halves mirror each other.
+    #[default]
+    Center,
+
+    /// Baseline at the bottom edge: the top series gets the full height and
+    /// grows upwards, like a classic area chart.
+    Bottom,
+
+    /// Baseline at the top edge: the bottom series gets the full height and
+    /// grows downwards.
+    Top,
+
+    /// Fraction (0.0 - 1.0) of the height given to the top half, the rest goes
+    /// to the bottom half.
+    Ratio(f64),
+}
+
+/// A value band that overrides the series style while the signal is inside it.
+///
+/// Zones are evaluated sequentially over the samples with hysteresis: a zone is
+/// entered once a value reaches `enter` and only left when a value drops below
+/// `exit`, so colors don't flicker when a signal oscillates around a boundary.
+/// Levels use the same units as the data (like [`Marker`]) and are compared
+/// against the raw values, before `top_max`/`bottom_max` scaling: with
+/// `top_max(500.0)`, a zone entered at `400.0` starts at a value of 400.
+///
+/// # Example
+/// ```rust
+/// use waveformchart::{WaveformWidget, Zone};
+/// use ratatui::style::{Style, Color};
+///
+/// let cpu = vec![0.5, 0.95, 0.85, 0.7];
+/// // Red above 90%, until the load drops back under 80%.
+/// let zones = [Zone::hysteresis(0.9, 0.8, Style::default().fg(Color::Red))];
+/// let widget = WaveformWidget::new(&cpu, &[]).top_zones(&zones);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Zone {
+    /// Value at or above which the zone is entered.
+    pub enter: f64,
+    /// Value below which the zone is left again.
+    pub exit: f64,
+    /// Style patched over the series style inside the zone.
+    pub style: Style,
+}
+
+impl Zone {
+    /// A zone entered at `level` and left below it (no hysteresis).
+    pub fn new(level: f64, style: Style) -> Self {
+        Self { enter: level, exit: level, style }
+    }
+
+    /// A zone entered at `enter` and left once values drop below `exit`.
+    pub fn hysteresis(enter: f64, exit: f64, style: Style) -> Self {
+        Self { enter, exit: exit.min(enter), style }
+    }
+}
+
+/// A labeled horizontal baseline within one half, e.g. "committed" memory.
+///
+/// The line is drawn beneath the waveform; its label sits at the right edge,
+/// on top of the data so it stays readable. Values use the same units as the data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Marker<'a> {
+    /// Value at which the line is drawn (scaled like the data of its half).
+    pub value: f64,
+    /// Short label shown at the right edge.
+    pub label: &'a str,
+    /// Style of the line and label.
+    pub style: Style,
+}
+
+impl<'a> Marker<'a> {
+    /// Creates a dark gray marker at `value`.
+    pub fn new(value: f64, label: &'a str) -> Self {
+        Self { value, label, style: Style::default().fg(Color::DarkGray) }
+    }
+
+    /// Sets the style of the line and label.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+/// A named aggregate of the samples in view, shown by the stats overlay
+/// (see [`WaveformWidget::stats`]).
+///
+/// `fold` receives the samples of one half in view, gaps left out, e.g. a
+/// 99th percentile or a crest factor next to the built-in min, max and avg.
+#[derive(Debug, Clone, Copy)]
+pub struct Aggregate<'a> {
+    /// Short name shown before the value.
+    pub name: &'a str,
+    /// Computes the value from the samples in view.
+    pub fold: fn(&[f64]) -> f64,
+    /// Localized name of the built-in aggregates, `None` for custom ones.
+    label: Option<Label>,
+}
+
+impl<'a> Aggregate<'a> {
+    /// Smallest sample in view.
+    pub const MIN: Aggregate<'static> =
+        Aggregate {
+        name: "min",
+        fold: |data| data.iter().copied().fold(f64::INFINITY, f64::min),
+        label: Some(Label::Min),
+    };
+
+    /// Largest sample in view.
+    pub const MAX: Aggregate<'static> =
+        Aggregate {
+        name: "max",
+        fold: |data| data.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        label: Some(Label::Max),
+    };
+
+    /// Mean of the samples in view.
+    pub const AVG: Aggregate<'static> =
+        Aggregate {
+        name: "avg",
+        fold: |data| data.iter().sum::<f64>() / data.len() as f64,
+        label: Some(Label::Avg),
+    };
+
+    /// Creates a custom aggregate shown as `name`, computed by `fold`.
+    ///
+    /// The name is shown as is, even when it matches a built-in one: only
+    /// [`MIN`](Self::MIN), [`MAX`](Self::MAX) and [`AVG`](Self::AVG) go
+    /// through the [`localize`](WaveformWidget::localize) hook.
+    pub fn new(name: &'a str, fold: fn(&[f64]) -> f64) -> Self {
+        Self { name, fold, label: None }
+    }
+}
+
+/// One latency band of an SLO heat-banding chart, see [`WaveformWidget::slo`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SloBand<'a> {
+    /// Value (same units as the data) at which the band starts.
+    pub from: f64,
+    /// Short name shown on the band's lower boundary, e.g. "slow".
+    pub label: &'a str,
+    /// Style of columns whose value falls into the band.
+    pub style: Style,
+}
+
+impl<'a> SloBand<'a> {
+    /// A band starting at `from` (in data units), labeled `label`.
+    pub fn new(from: f64, label: &'a str, style: Style) -> Self {
+        Self { from, label, style }
+    }
 }
 
 /// A Ratatui widget for rendering high-resolution waveform charts.
@@ -50,440 +430,3879 @@ pub struct WaveformWidget<'a> {
     /// The active rendering mode.
     mode: WaveformMode,
 
-    /// Normalized data (0.0 - 1.0) rendered upwards from center.
-    top_data: &'a [f64],
-    top_style: Style,
+    /// Series rendered upwards from center.
+    top: Series<'a>,
 
-    /// Normalized data (0.0 - 1.0) rendered downwards from center.
-    bottom_data: &'a [f64],
-    bottom_style: Style,
+    /// Series rendered downwards from center.
+    bottom: Series<'a>,
 
     /// If true, applies a horizontal fade effect (dimming older data).
     fade_effect: bool,
+
+    /// Fraction of the width, from the newest edge, kept at full brightness.
+    fade_start: f64,
+
+    /// Brightness fall-off of the fading part.
+    fade_curve: FadeCurve,
     
     /// If true, applies a vertical gradient effect (color changes with height).
     gradient_effect: bool,
 
-    /// Maximum value for scaling (default 1.0)
-    top_max: f64,
-    bottom_max: f64,
-}
+    /// End of the columns the vertical gradient keeps brightest.
+    gradient_direction: GradientDirection,
 
-impl<'a> WaveformWidget<'a> {
-    /// Creates a new widget with required data references.
-    /// Data must be normalized between 0.0 and 1.0.
-    pub fn new(top_data: &'a [f64], bottom_data: &'a [f64]) -> Self {
-        Self {
-            top_data,
-            bottom_data,
-            block: None,
-            mode: WaveformMode::HighResBraille,
-            fade_effect: false,
-            gradient_effect: false,
-            top_style: Style::default(),
-            bottom_style: Style::default(),
-            top_max: 1.0,
-            bottom_max: 1.0,
-        }
-    }
+    /// Dimming of the vertical gradient at its dim end (0.0 - 1.0).
+    gradient_strength: f64,
 
-    /// Sets an optional surrounding block.
-    pub fn block(mut self, block: Block<'a>) -> Self {
-        self.block = Some(block);
-        self
-    }
+    /// Color stops (sorted by height) replacing the foreground per row.
+    gradient_stops: Vec<(f64, Color)>,
 
-    /// Sets the rendering mode.
-    pub fn mode(mut self, mode: WaveformMode) -> Self {
-        self.mode = mode;
-        self
-    }
+    /// Color stops (sorted by value) replacing the foreground per column.
+    value_stops: Vec<(f64, Color)>,
 
-    /// Sets the style (color, modifier) for the top half.
-    pub fn top_style(mut self, style: Style) -> Self {
-        self.top_style = style;
-        self
-    }
+    /// Draw a faint vertical separator every N samples (by sample number).
+    separator_interval: Option<usize>,
+    separator_style: Style,
 
-    /// Sets the style (color, modifier) for the bottom half.
-    pub fn bottom_style(mut self, style: Style) -> Self {
-        self.bottom_style = style;
-        self
-    }
+    /// Data index ranges highlighted with a style over the full height.
+    regions: Vec<(Range<usize>, Style)>,
 
-    /// Enables or disables the horizontal fade effect.
-    pub fn fade_effect(mut self, enable: bool) -> Self {
-        self.fade_effect = enable;
-        self
-    }
+    /// Value interval and style of the horizontal grid lines, `None` for none.
+    grid: Option<(f64, Style)>,
 
-    /// Enables or disables the vertical gradient effect.
-    pub fn gradient_effect(mut self, enable: bool) -> Self {
-        self.gradient_effect = enable;
-        self
-    }
+    /// Whether grid lines and separators are drawn with Braille dots.
+    grid_dots: bool,
 
-    pub fn top_max(mut self, max: f64) -> Self {
-        self.top_max = max;
-        self
-    }
+    /// Optional style patched onto the outermost cell of each column.
+    edge_style: Option<Style>,
 
-    pub fn bottom_max(mut self, max: f64) -> Self {
-        self.bottom_max = max;
-        self
-    }
-}
+    /// Optional style patched onto the outermost cell of each column after the fade.
+    peak_style: Option<Style>,
 
-impl<'a> Widget for WaveformWidget<'a> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let inner_area = match &self.block {
-            Some(b) => {
-                let inner = b.inner(area);
-                b.render(area, buf);
-                inner
-            }
-            None => area,
-        };
+    /// If true, histogram-equalizes the visible window (nonlinear view).
+    equalize: bool,
 
-        if inner_area.height < 1 || inner_area.width < 1 {
-            return;
-        }
+    /// If true, each half is reflected (dimmed) into the opposite half.
+    reflection_effect: bool,
 
-        let center_y = inner_area.top() + (inner_area.height / 2);
-        let max_char_height = inner_area.height / 2;
-        
-        let data_len = self.top_data.len().min(self.bottom_data.len());
-        let width = inner_area.width as usize;
-        let start_x_offset = width.saturating_sub(data_len) as u16;
+    /// Number of cells each sample occupies horizontally (at least 1).
+    bar_width: usize,
+    /// Number of blank cells between consecutive bars.
+    bar_gap: usize,
 
-        for x in inner_area.left()..inner_area.right() {
-            let relative_x = x - inner_area.left();
-            
-            if relative_x < start_x_offset {
-                continue;
-            }
-            
-            let data_index = (relative_x - start_x_offset) as usize;
-            
-            // Bounds check
-            if data_index >= self.top_data.len() || data_index >= self.bottom_data.len() {
-                continue;
-            }
+    /// Fraction of a cell (0.0 - 1.0) the data has scrolled past its cell.
+    scroll_phase: f64,
 
-            // Normalize data based on max value (default 1.0)
-            let top_val = (self.top_data[data_index] / self.top_max).clamp(0.0, 1.0);
-            let bottom_val = (self.bottom_data[data_index] / self.bottom_max).clamp(0.0, 1.0);
-
-            // Calculate fade factor
-            let fade_factor = if self.fade_effect {
-                let relative_x_f = (x - inner_area.left()) as f64;
-                let width_f = inner_area.width as f64;
-                // 0.0 (left) to 1.0 (right)
-                // We want right to be 1.0 (bright), left to be 0.0 (invisible)
-                // Using a power curve makes the fade more dramatic
-                let linear = relative_x_f / width_f;
-                // Delayed fade: Right half (0.5-1.0) is full brightness
-                // Left half (0.0-0.5) fades linearly from 0.0 to 1.0
-                if linear > 0.5 {
-                    1.0
-                } else {
-                    linear * 2.0
-                }
-            } else {
-                1.0
-            };
+    /// Color capabilities of the terminal, used by the fade and gradient.
+    color_depth: ColorDepth,
 
-            // Base styles (no fade yet)
-            let top_base_style = self.top_style;
-            let bottom_base_style = self.bottom_style;
+    /// Color space the fade and gradient dim and blend in.
+    color_space: ColorSpace,
 
-            match self.mode {
-                WaveformMode::HighResBraille => {
-                    self.render_braille_column(buf, x, center_y, max_char_height, top_val, true, top_base_style, self.gradient_effect, fade_factor);
-                    self.render_braille_column(buf, x, center_y, max_char_height, bottom_val, false, bottom_base_style, self.gradient_effect, fade_factor);
-                }
-                WaveformMode::UltraThinBlock => {
-                    self.render_block_column(buf, x, center_y, max_char_height, top_val, true, inner_area, top_base_style, self.gradient_effect, fade_factor);
-                    self.render_block_column(buf, x, center_y, max_char_height, bottom_val, false, inner_area, bottom_base_style, self.gradient_effect, fade_factor);
-                }
-            }
-        }
-    }
-}
+    /// Direction in which time flows.
+    orientation: Orientation,
 
-fn apply_fade(style: Style, factor: f64) -> Style {
-    // Removed early return to ensure consistent RGB conversion
-    // even when factor is 1.0. This prevents "Named Color" vs "RGB Color" mismatches.
-    
-    // Apply DIM modifier for extra fading hint
-    // Removed DIM modifier as it might cause desaturation on some terminals
-    // if factor < 0.5 {
-    //    style = style.add_modifier(ratatui::style::Modifier::DIM);
-    // }
-    
-    let (r, g, b) = match style.fg {
-        Some(c) => color_to_rgb(c),
-        None => return style,
-    };
+    /// Whether solid block tips fill their cell in eighths, set while a
+    /// vertical chart is drawn unrotated.
+    eighths: bool,
 
-    let new_r = (r as f64 * factor) as u8;
-    let new_g = (g as f64 * factor) as u8;
-    let new_b = (b as f64 * factor) as u8;
-
-    style.fg(Color::Rgb(new_r, new_g, new_b))
-}
-
-fn color_to_rgb(color: Color) -> (u8, u8, u8) {
-    match color {
-        Color::Rgb(r, g, b) => (r, g, b),
-        Color::Indexed(i) => {
-             match i {
-                 // Standard 16 colors approximation
-                 0 => (0, 0, 0), // Black
-                 1 => (170, 0, 0), // Red
-                 2 => (0, 170, 0), // Green
-                 3 => (170, 85, 0), // Yellow
-                 4 => (0, 0, 170), // Blue
-                 5 => (170, 0, 170), // Magenta
-                 6 => (0, 170, 170), // Cyan
-                 7 => (170, 170, 170), // Gray
-                 8 => (85, 85, 85), // DarkGray
-                 9 => (255, 85, 85), // LightRed
-                 10 => (85, 255, 85), // LightGreen
-                 11 => (255, 255, 85), // LightYellow
-                 12 => (85, 85, 255), // LightBlue
-                 13 => (255, 85, 255), // LightMagenta
-                 14 => (85, 255, 255), // LightCyan
-                 15 => (255, 255, 255), // White
-                 _ => (255, 255, 255), // Default to white for unknown
-             }
-        },
-        Color::Black => (0, 0, 0),
-        Color::Red => (170, 0, 0),
-        Color::Green => (0, 170, 0),
-        Color::Yellow => (170, 85, 0),
-        Color::Blue => (0, 0, 170),
-        Color::Magenta => (170, 0, 170),
-        Color::Cyan => (0, 170, 170),
-        Color::Gray => (170, 170, 170),
-        Color::DarkGray => (85, 85, 85),
-        Color::LightRed => (255, 85, 85),
-        Color::LightGreen => (85, 255, 85),
-        Color::LightYellow => (255, 255, 85),
-        Color::LightBlue => (85, 85, 255),
-        Color::LightMagenta => (255, 85, 255),
-        Color::LightCyan => (85, 255, 255),
-        Color::White => (255, 255, 255),
-        _ => (255, 255, 255),
-    }
-}
+    /// Position of the line separating the halves.
+    baseline: Baseline,
 
-impl<'a> WaveformWidget<'a> {
-    fn render_braille_column(
-        &self,
-        buf: &mut Buffer,
-        x: u16,
-        center_y: u16,
-        max_char_height: u16,
-        val: f64,
-        is_top: bool,
-        base_style: Style,
-        use_gradient: bool,
-        fade_factor: f64,
-    ) {
-        let total_dots = max_char_height as f64 * 4.0;
-        let needed_dots = (val * total_dots).round() as u16;
-        
-        let mut dots_remaining = needed_dots;
-        let mut y = if is_top { center_y.saturating_sub(1) } else { center_y };
-
-        for i in 0..max_char_height {
-            if dots_remaining == 0 {
-                break;
-            }
-
-            let char_to_draw = if dots_remaining >= 4 {
-                dots_remaining -= 4;
-                '\u{2847}' // Full height ⡇
-            } else {
-                let c = if is_top {
-                    get_thin_braille_fill(dots_remaining as u8)
-                } else {
-                    get_thin_braille_fill_bottom(dots_remaining as u8)
-                };
-                dots_remaining = 0;
-                c
-            };
-            
-            let style = if use_gradient {
-                // Calculate height ratio (0.0 at center, 1.0 at peak)
-                let height_ratio = i as f64 / max_char_height as f64;
-                apply_gradient(base_style, height_ratio)
-            } else {
-                base_style
-            };
-            
-            // Apply fade LAST so it dims whatever color we have
-            let final_style = apply_fade(style, fade_factor);
+    /// Blank rows reserved between the halves.
+    center_gap: u16,
 
-            buf[(x, y)].set_char(char_to_draw).set_style(final_style);
+    /// Translates the text the widget generates.
+    localize: Option<Localize<'a>>,
 
-            if is_top {
-                if y == 0 { break; } // Prevent underflow
-                y -= 1;
-            } else {
-                y += 1;
-            }
-        }
-    }
+    /// Units of the top and bottom axis labels, `None` to hide the labels.
+    axis_units: Option<(&'a str, &'a str)>,
 
-    fn render_block_column(
-        &self,
-        buf: &mut Buffer,
-        x: u16,
-        center_y: u16,
-        max_char_height: u16,
-        val: f64,
-        is_top: bool,
-        inner_area: Rect,
-        base_style: Style,
-        use_gradient: bool,
-        fade_factor: f64,
-    ) {
-        let needed_rows = (val * max_char_height as f64).round() as u16;
+    /// Formats the axis label values, `None` for the compact number and unit.
+    axis_format: Option<AxisFormat<'a>>,
 
-        for i in 0..needed_rows {
-             let y = if is_top {
-                (center_y.saturating_sub(1)).saturating_sub(i)
-            } else {
-                center_y + i
-            };
+    /// Whether the newest value of each half is printed at the newest edge.
+    show_last_value: bool,
 
-            // Bounds check
-            if is_top {
-                if y < inner_area.top() { continue; }
-            } else {
-                if y >= inner_area.bottom() { continue; }
-            }
-            
-            let style = if use_gradient {
-                let height_ratio = i as f64 / max_char_height as f64;
-                apply_gradient(base_style, height_ratio)
-            } else {
-                base_style
-            };
+    /// Data index the crosshair is drawn at, `None` for no crosshair.
+    cursor: Option<usize>,
 
-            // Apply fade LAST
-            let final_style = apply_fade(style, fade_factor);
+    /// Style of the crosshair line.
+    cursor_style: Style,
 
-            buf[(x, y)].set_char('▌').set_style(final_style);
-        }
-    }
-}
+    /// Buffer cell under the mouse, whose column is highlighted.
+    hover: Option<(u16, u16)>,
 
-fn apply_gradient(style: Style, ratio: f64) -> Style {
-    // Inverted Gradient:
-    // Center (ratio 0.0) = Full Brightness (1.0)
-    // Peak (ratio 1.0) = Dimmer (e.g. 30% brightness)
-    
-    if let Some(color) = style.fg {
-        let (r, g, b) = color_to_rgb(color);
+    /// Style patched over the hovered column.
+    hover_style: Style,
 
-        // Brightness decreases as we go away from center
-        let brightness = 1.0 - (ratio * 0.7);
-        
-        let new_r = (r as f64 * brightness) as u8;
-        let new_g = (g as f64 * brightness) as u8;
-        let new_b = (b as f64 * brightness) as u8;
-        
-        style.fg(Color::Rgb(new_r, new_g, new_b))
-    } else {
-        style
-    }
-}
+    /// Aggregates shown in the stats overlay of each half.
+    stats: Vec<Aggregate<'a>>,
 
-// This function must only be called when mode is HighResBraille.
-// height_in_dots must be between 1 and 4 inclusive.
-fn get_thin_braille_fill(height_in_dots: u8) -> char {
-    match height_in_dots {
-        // Dot 7 only
-        1 => '\u{2840}', // ⡀
-        // Dots 7 and 3
-        2 => '\u{2844}', // ⡄
-        // Dots 7, 3, and 2
-        3 => '\u{2846}', // ⡆
-        // Dots 7, 3, 2, and 1 (Full left column)
-        4 => '\u{2847}', // ⡇
-        // Fallback for safety
-        _ => ' ',
-    }
-}
+    /// Style of the markers drawn for gaps (NaN samples).
+    gap_style: Option<Style>,
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Style of the gap markers after the newest real sample.
+    stale_style: Option<Style>,
 
-    #[test]
-    fn test_get_thin_braille_fill() {
-        assert_eq!(get_thin_braille_fill(1), '\u{2840}');
-        assert_eq!(get_thin_braille_fill(2), '\u{2844}');
-        assert_eq!(get_thin_braille_fill(3), '\u{2846}');
-        assert_eq!(get_thin_braille_fill(4), '\u{2847}');
-        assert_eq!(get_thin_braille_fill(0), ' ');
-        assert_eq!(get_thin_braille_fill(5), ' ');
-    }
+    /// Style patched over the tip of columns exceeding the half's max.
+    clip_style: Option<Style>,
 
-    #[test]
-    fn test_get_thin_braille_fill_bottom() {
-        assert_eq!(get_thin_braille_fill_bottom(1), '\u{2801}');
-        assert_eq!(get_thin_braille_fill_bottom(2), '\u{2803}');
-        assert_eq!(get_thin_braille_fill_bottom(3), '\u{2807}');
-        assert_eq!(get_thin_braille_fill_bottom(4), '\u{2847}');
-        assert_eq!(get_thin_braille_fill_bottom(0), ' ');
-        assert_eq!(get_thin_braille_fill_bottom(5), ' ');
-    }
+    /// Observer called with every column drawn.
+    on_render_column: Option<ColumnObserver<'a>>,
 
-    #[test]
-    fn test_apply_fade() {
-        let style = Style::default().fg(Color::Rgb(100, 200, 50));
-        
-        // 100% factor -> Same color
-        let faded_100 = apply_fade(style, 1.0);
-        assert_eq!(faded_100.fg, Some(Color::Rgb(100, 200, 50)));
+    /// Style of the drawn axis line along the baseline.
+    baseline_style: Option<Style>,
 
-        // 50% factor -> Half brightness
-        let faded_50 = apply_fade(style, 0.5);
-        assert_eq!(faded_50.fg, Some(Color::Rgb(50, 100, 25)));
+    /// Glyph of the drawn axis line along the baseline.
+    baseline_char: Option<char>,
 
-        // 0% factor -> Black
-        let faded_0 = apply_fade(style, 0.0);
-        assert_eq!(faded_0.fg, Some(Color::Rgb(0, 0, 0)));
-    }
+    /// Style of the corner ticks and axis arrowhead, `None` for no frame.
+    frame_style: Option<Style>,
 
-    #[test]
-    fn test_apply_gradient() {
-        let style = Style::default().fg(Color::Rgb(0, 0, 255)); // Blue
-        
-        // 0% ratio (Center) -> Full Brightness
-        // B: 255 * 1.0 = 255
-        let grad_0 = apply_gradient(style, 0.0);
-        assert_eq!(grad_0.fg, Some(Color::Rgb(0, 0, 255)));
+    /// Style of the whole chart area beneath everything else, `None` to keep the buffer's.
+    background_style: Option<Style>,
 
-        // 100% ratio (Peak) -> Dimmer (30% brightness)
-        // B: 255 * 0.3 = 76.5 -> 76
-        let grad_100 = apply_gradient(style, 1.0);
-        assert_eq!(grad_100.fg, Some(Color::Rgb(0, 0, 76)));
-    }
-}
+    /// Style of the cells between the baseline and the waveform, `None` for none.
+    under_curve_style: Option<Style>,
 
-// This function must only be called when mode is HighResBraille.
-// height_in_dots must be between 1 and 4 inclusive.
-// Returns characters with dots aligned to the TOP of the cell (for growing downwards).
-fn get_thin_braille_fill_bottom(height_in_dots: u8) -> char {
-    match height_in_dots {
-        // Dot 1 only (Top Left)
-        1 => '\u{2801}', // ⠁
-        // Dots 1 and 2 (Top two)
+    /// Glyph sets the target terminal can display.
+    glyph_support: GlyphSupport,
+
+    /// Size of the inset overview of the full history and the style of its
+    /// visible-window highlight, `None` for no overview.
+    overview: Option<(u16, u16, Style)>,
+
+    /// Horizontal placement of data shorter than the width.
+    alignment: Alignment,
+
+    /// Number of newest samples scrolled out of view (0 = following the tail).
+    offset: usize,
+
+    /// Aggregation of data longer than the width, `None` to show the newest samples only.
+    downsample: Option<Downsample>,
+
+    /// Side of the chart the newest sample appears on.
+    direction: Direction,
+
+    /// How data shorter than the width is spread over it.
+    fit: Fit,
+
+    /// Scaling of stacked layers.
+    stacking: Stacking,
+
+    /// Horizontal zoom: columns per sample.
+    zoom: f64,
+
+    /// Mapping of values to column heights.
+    scale: Scale,
+
+    /// Headroom above the visible peak and the percentile (0 - 100) taken as
+    /// the peak when autoscaling, `None` for fixed scales.
+    autoscale: Option<(f64, f64)>,
+
+    /// Per-sample decay of the samples before the visible window when
+    /// autoscaling, 0.0 to scale to the visible samples only.
+    autoscale_decay: f64,
+
+    /// Time of the frame being drawn, driving the time-based effects.
+    frame_time: Duration,
+
+    /// Whether the burn-in protection shift is enabled.
+    burn_in: bool,
+
+    /// Whether heights are held across frames when rendered with a [`ScrollState`].
+    hysteresis: bool,
+
+    /// Whether waveform cell styles go through a per-frame palette.
+    style_palette: bool,
+
+    /// Palette of the frame being drawn, while `style_palette` is enabled.
+    palette: Option<RefCell<Palette>>,
+
+    /// Whether the newest column uses the dot column at the outer edge.
+    flush_edge: bool,
+
+    /// Cell column of the newest sample while rendering with `flush_edge`.
+    edge_x: Option<u16>,
+
+    /// Index range of the data to render, `None` for all of it.
+    window: Option<Range<usize>>,
+
+    /// Handling of NaN and infinite samples.
+    invalid: Invalid,
+
+    /// Compression applied near the top of the range, `None` to hard-clamp.
+    soft_clip: Option<SoftClip>,
+}
+
+/// Data and per-half settings of one series.
+struct Series<'a> {
+    /// Normalized data (0.0 - 1.0).
+    data: Samples<'a>,
+    style: Style,
+    /// Maximum value for scaling (default 1.0)
+    max: f64,
+    /// Value at the baseline (default 0.0); lower values are drawn empty.
+    min: f64,
+    /// Value zones (sorted by `enter`) overriding the style.
+    zones: Vec<Zone>,
+    /// Labeled baselines within the half.
+    markers: Vec<Marker<'a>>,
+    /// Style lookup by sample index, backed by a caller-supplied metadata slice.
+    metadata: Option<MetadataStyle<'a>>,
+    /// Style computed from each sample's index and value.
+    style_fn: Option<StyleFn<'a>>,
+    /// Lower ends of the columns for envelope input (same scale as `data`).
+    lows: Option<Cow<'a, [f64]>>,
+    /// Reference series drawn dithered beneath the data, on the same scale.
+    underlay: Option<&'a [f64]>,
+    /// Ascending sample times, switching to time-weighted buckets.
+    timestamps: Option<&'a [f64]>,
+    /// Layers stacked above the data, bottom-up, indexed like `data`.
+    layers: Vec<(&'a [f64], Style)>,
+    /// Smallest change that is drawn (same scale as `data`), 0.0 to draw all.
+    dead_band: f64,
+    /// Brightness multiplier of the half (1.0 = unchanged).
+    intensity: f64,
+    /// Rise per column (fraction of the half) and style of the min-hold trace.
+    min_hold: Option<(f64, Style)>,
+    /// Level (same scale as `data`) and style of the peak-hold line.
+    peak_hold: Option<(f64, Style)>,
+    /// Normalized values held from the last frame by the hysteresis, by data index.
+    held: HashMap<usize, f64>,
+    /// Sample number of `data[0]`: samples dropped from a streaming history before it.
+    first: usize,
+    /// Sample number and the dead band's held value entering it, carried from
+    /// the last frame by a [`ScrollState`].
+    dead_band_anchor: Option<(usize, f64)>,
+}
+
+type MetadataStyle<'a> = Box<dyn Fn(usize) -> Option<Style> + 'a>;
+
+type StyleFn<'a> = Box<dyn Fn(usize, f64) -> Style + 'a>;
+
+/// Sample storage of a series: `f64` data is used as-is, other sample types
+/// are converted on the fly, only for the samples in view (and up to
+/// [`LOOK_BACK`] before them).
+enum Samples<'a> {
+    Floats(Cow<'a, [f64]>),
+    Converted { len: usize, get: Box<dyn Fn(usize) -> f64 + 'a>, missing: Box<dyn Fn(usize) -> bool + 'a> },
+}
+
+impl<'a> Samples<'a> {
+    fn from_slice<T: Sample>(data: &'a [T]) -> Self {
+        Samples::Converted {
+            len: data.len(),
+            get: Box::new(move |i| data[i].to_f64()),
+            missing: Box::new(move |i| data[i].is_missing()),
+        }
+    }
+
+    fn from_source<S: SampleSource + ?Sized>(source: &'a S) -> Self {
+        Samples::Converted { len: source.len(), get: Box::new(move |i| source.sample(i)), missing: Box::new(|_| false) }
+    }
+
+    /// Borrows the samples, e.g. for a second widget over the same data.
+    fn borrow(&self) -> Samples<'_> {
+        match self {
+            Samples::Floats(data) => Samples::Floats(Cow::Borrowed(data)),
+            Samples::Converted { len, get, missing } => {
+                Samples::Converted { len: *len, get: Box::new(get), missing: Box::new(missing) }
+            }
+        }
+    }
+
+    /// View of the samples in `range`, indexed from its start.
+    fn slice(&self, range: Range<usize>) -> Samples<'_> {
+        match self {
+            Samples::Floats(data) => Samples::Floats(Cow::Borrowed(&data[range])),
+            Samples::Converted { get, missing, .. } => {
+                let start = range.start;
+                Samples::Converted {
+                    len: range.len(),
+                    get: Box::new(move |i| get(start + i)),
+                    missing: Box::new(move |i| missing(start + i)),
+                }
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Samples::Floats(data) => data.len(),
+            Samples::Converted { len, .. } => *len,
+        }
+    }
+
+    /// Whether sample `index` is missing (`None`), rather than invalid.
+    fn missing(&self, index: usize) -> bool {
+        match self {
+            Samples::Floats(_) => false,
+            Samples::Converted { missing, .. } => missing(index),
+        }
+    }
+
+    /// Values of the samples in `range`, borrowed when possible.
+    fn values(&self, range: Range<usize>) -> Cow<'_, [f64]> {
+        match self {
+            Samples::Floats(data) => Cow::Borrowed(&data[range]),
+            Samples::Converted { get, .. } => Cow::Owned(range.map(get).collect()),
+        }
+    }
+}
+
+/// Numeric sample types the widget draws directly, see
+/// [`WaveformWidget::from_samples`].
+pub trait Sample: Copy {
+    /// The sample as `f64`, before scaling.
+    fn to_f64(self) -> f64;
+
+    /// Whether the sample is missing, drawn as a gap whatever the
+    /// [`invalid`](WaveformWidget::invalid) policy (default: never).
+    fn is_missing(self) -> bool {
+        false
+    }
+}
+
+macro_rules! impl_sample {
+    ($($t:ty),*) => {
+        $(impl Sample for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+
+impl_sample!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// Missing samples (`None`), e.g. dropped by a network source, are drawn as
+/// gaps rather than zeros, whatever the [`invalid`](WaveformWidget::invalid)
+/// policy. Pair with [`WaveformWidget::gap_style`] to mark them.
+impl<T: Sample> Sample for Option<T> {
+    fn to_f64(self) -> f64 {
+        self.map_or(f64::NAN, Sample::to_f64)
+    }
+
+    fn is_missing(self) -> bool {
+        self.is_none_or(Sample::is_missing)
+    }
+}
+
+/// Random-access sample storage the widget reads on demand, e.g. a
+/// memory-mapped file or a database-backed store, see
+/// [`WaveformWidget::from_sources`]. Only the samples in view are read, plus
+/// up to 4096 before them for the [`top_dead_band`](WaveformWidget::top_dead_band)
+/// and [`autoscale_decay`](WaveformWidget::autoscale_decay) options.
+pub trait SampleSource {
+    /// Value of the sample at `index` (`0..len()`), before scaling.
+    fn sample(&self, index: usize) -> f64;
+
+    /// Number of samples.
+    fn len(&self) -> usize;
+
+    /// True if the source holds no samples.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+type Localize<'a> = Box<dyn Fn(Label) -> Option<String> + 'a>;
+
+type AxisFormat<'a> = Box<dyn Fn(f64) -> String + 'a>;
+
+/// Axis labels of the top and bottom halves as (row from the center, text).
+type AxisLabels = (Vec<(u16, String)>, Vec<(u16, String)>);
+
+type ColumnObserver<'a> = Box<dyn FnMut(&RenderedColumn) + 'a>;
+
+impl<'a> Series<'a> {
+    fn new(data: Samples<'a>) -> Self {
+        Self {
+            data,
+            style: Style::default(),
+            max: 1.0,
+            min: 0.0,
+            zones: Vec::new(),
+            markers: Vec::new(),
+            metadata: None,
+            style_fn: None,
+            lows: None,
+            underlay: None,
+            timestamps: None,
+            layers: Vec::new(),
+            dead_band: 0.0,
+            intensity: 1.0,
+            min_hold: None,
+            peak_hold: None,
+            held: HashMap::new(),
+            first: 0,
+            dead_band_anchor: None,
+        }
+    }
+}
+
+impl<'a> WaveformWidget<'a> {
+    /// Creates a new widget with required data references.
+    /// Data must be normalized between 0.0 and 1.0.
+    pub fn new(top_data: &'a [f64], bottom_data: &'a [f64]) -> Self {
+        Self::from_data(Samples::Floats(Cow::Borrowed(top_data)), Samples::Floats(Cow::Borrowed(bottom_data)))
+    }
+
+    /// Creates a new widget from any numeric sample type (`f32` audio buffers,
+    /// `u64` counters, `Option<f64>` with missing samples, ...) without
+    /// converting the data up front: only the samples in view (and the few
+    /// thousand before them a dead band or autoscale decay looks back on) are
+    /// converted while rendering.
+    ///
+    /// # Example
+    /// ```rust
+    /// use waveformchart::WaveformWidget;
+    ///
+    /// let left: Vec<f32> = vec![0.25, 0.5];
+    /// let requests: Vec<u64> = vec![120, 80];
+    /// let widget = WaveformWidget::from_samples(&left, &requests).bottom_max(200.0);
+    /// ```
+    pub fn from_samples<T: Sample, U: Sample>(top_data: &'a [T], bottom_data: &'a [U]) -> Self {
+        Self::from_data(Samples::from_slice(top_data), Samples::from_slice(bottom_data))
+    }
+
+    /// Creates a new widget reading its samples from [`SampleSource`]s, so large
+    /// histories don't have to be materialized as slices.
+    ///
+    /// # Example
+    /// ```rust
+    /// use waveformchart::{SampleSource, WaveformWidget};
+    ///
+    /// struct Sine(usize);
+    ///
+    /// impl SampleSource for Sine {
+    ///     fn sample(&self, index: usize) -> f64 {
+    ///         (index as f64 / 10.0).sin().abs()
+    ///     }
+    ///
+    ///     fn len(&self) -> usize {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let (top, bottom) = (Sine(1_000_000), Sine(0));
+    /// let widget = WaveformWidget::from_sources(&top, &bottom);
+    /// ```
+    pub fn from_sources<S: SampleSource + ?Sized, T: SampleSource + ?Sized>(top: &'a S, bottom: &'a T) -> Self {
+        Self::from_data(Samples::from_source(top), Samples::from_source(bottom))
+    }
+
+    fn from_data(top_data: Samples<'a>, bottom_data: Samples<'a>) -> Self {
+        Self {
+            top: Series::new(top_data),
+            bottom: Series::new(bottom_data),
+            block: None,
+            mode: WaveformMode::HighResBraille,
+            fade_effect: false,
+            fade_start: 0.5,
+            fade_curve: FadeCurve::Linear,
+            gradient_effect: false,
+            gradient_direction: GradientDirection::BrightCenter,
+            gradient_strength: color::GRADIENT_STRENGTH,
+            gradient_stops: Vec::new(),
+            value_stops: Vec::new(),
+            separator_interval: None,
+            regions: Vec::new(),
+            grid: None,
+            grid_dots: false,
+            separator_style: Style::default().fg(Color::DarkGray),
+            edge_style: None,
+            peak_style: None,
+            equalize: false,
+            reflection_effect: false,
+            bar_width: 1,
+            bar_gap: 0,
+            scroll_phase: 0.0,
+            color_depth: ColorDepth::TrueColor,
+            color_space: ColorSpace::Rgb,
+            orientation: Orientation::Horizontal,
+            eighths: false,
+            baseline: Baseline::Center,
+            center_gap: 0,
+            localize: None,
+            axis_units: None,
+            axis_format: None,
+            show_last_value: false,
+            cursor: None,
+            cursor_style: Style::default().fg(Color::Gray),
+            hover: None,
+            hover_style: Style::default().bg(Color::DarkGray),
+            stats: Vec::new(),
+            on_render_column: None,
+            gap_style: None,
+            clip_style: None,
+            stale_style: None,
+            baseline_style: None,
+            baseline_char: None,
+            frame_style: None,
+            background_style: None,
+            under_curve_style: None,
+            glyph_support: GlyphSupport::FULL,
+            overview: None,
+            alignment: Alignment::Right,
+            offset: 0,
+            downsample: None,
+            direction: Direction::LeftToRight,
+            fit: Fit::Natural,
+            stacking: Stacking::Absolute,
+            zoom: 1.0,
+            scale: Scale::Linear,
+            autoscale: None,
+            autoscale_decay: 0.0,
+            frame_time: Duration::ZERO,
+            burn_in: false,
+            hysteresis: false,
+            style_palette: false,
+            palette: None,
+            flush_edge: false,
+            edge_x: None,
+            window: None,
+            invalid: Invalid::Skip,
+            soft_clip: None,
+        }
+    }
+
+    /// Creates a widget that owns its data.
+    ///
+    /// The widget is `'static`, so it can be built in one part of the app and
+    /// stored or sent elsewhere to be rendered later. [`new`](Self::new) stays
+    /// the zero-copy path for data that outlives the frame.
+    pub fn owned(top_data: Vec<f64>, bottom_data: Vec<f64>) -> WaveformWidget<'static> {
+        WaveformWidget::from_data(Samples::Floats(Cow::Owned(top_data)), Samples::Floats(Cow::Owned(bottom_data)))
+    }
+
+    /// Creates a widget for a single series that uses the full height.
+    ///
+    /// The data grows upwards from the bottom edge, like an area chart.
+    /// Shorthand for an empty bottom half with [`Baseline::Bottom`]; the
+    /// `top_*` settings apply to the series.
+    pub fn single(data: &'a [f64]) -> Self {
+        Self::new(data, &[]).baseline(Baseline::Bottom)
+    }
+
+    /// Creates a widget for a single signed series in `-1.0..=1.0`, such as raw
+    /// audio samples, drawn around the center axis: positive values grow
+    /// upwards, negative ones downwards. The data is split while rendering,
+    /// so no separate top and bottom arrays are needed.
+    pub fn bipolar(data: &'a [f64]) -> Self {
+        // Invalid samples are kept in both halves, so they are handled alike.
+        let half = |sign: f64| Samples::Converted {
+            len: data.len(),
+            get: Box::new(move |i| {
+                let v = data[i];
+                if v.is_finite() { (v * sign).max(0.0) } else { v }
+            }),
+            missing: Box::new(|_| false),
+        };
+        Self::from_data(half(1.0), half(-1.0))
+    }
+
+    /// Preset for network throughput: `rx` grows upwards, `tx` downwards.
+    ///
+    /// Raw rates (bytes/s, packets/s) are accepted as-is; each half is scaled
+    /// to the peak of its own data. Uses green/blue Braille with a horizontal fade.
+    pub fn network(rx: &'a [f64], tx: &'a [f64]) -> Self {
+        Self::new(rx, tx)
+            .mode(WaveformMode::HighResBraille)
+            .top_style(Style::default().fg(Color::Green))
+            .bottom_style(Style::default().fg(Color::Blue))
+            .fade_effect(true)
+            .top_max(peak(rx))
+            .bottom_max(peak(tx))
+    }
+
+    /// Preset for audio: the sample amplitudes (0.0 - 1.0) are mirrored around
+    /// the center like a classic audio editor waveform, with a vertical gradient.
+    pub fn audio(samples: &'a [f64]) -> Self {
+        Self::new(samples, samples)
+            .mode(WaveformMode::HighResBraille)
+            .top_style(Style::default().fg(Color::Cyan))
+            .bottom_style(Style::default().fg(Color::Cyan))
+            .gradient_effect(true)
+    }
+
+    /// Preset for a single normalized metric (0.0 - 1.0), e.g. CPU usage.
+    /// Only the top half is drawn, using thin blocks on a fixed 100% scale,
+    /// with older columns faded.
+    pub fn metric(values: &'a [f64]) -> Self {
+        Self::new(values, &[])
+            .mode(WaveformMode::UltraThinBlock)
+            .top_style(Style::default().fg(Color::Green))
+            .fade_effect(true)
+    }
+
+    /// Creates a widget from per-column `(min, max)` pairs, e.g. a
+    /// pre-aggregated peak file of an audio recording.
+    ///
+    /// Each column spans the full range between its min and max, the way
+    /// audio editors draw peaks: positive parts in the top half, negative
+    /// parts (mirrored) in the bottom half. Values are in -1.0 - 1.0.
+    pub fn envelope(peaks: &[(f64, f64)]) -> WaveformWidget<'static> {
+        // Span of each pair within a half, as (lows, highs).
+        let half = |sign: f64| -> (Vec<f64>, Vec<f64>) {
+            peaks
+                .iter()
+                .map(|&(min, max)| {
+                    let (a, b) = ((min * sign).max(0.0), (max * sign).max(0.0));
+                    (a.min(b), a.max(b))
+                })
+                .unzip()
+        };
+        let (top_lows, top) = half(1.0);
+        let (bottom_lows, bottom) = half(-1.0);
+
+        let mut widget = WaveformWidget::owned(top, bottom);
+        widget.top.lows = Some(Cow::Owned(top_lows));
+        widget.bottom.lows = Some(Cow::Owned(bottom_lows));
+        widget
+    }
+
+    /// Companion view of the difference `top - bottom` of two series, e.g.
+    /// rx - tx imbalance or actual vs. target.
+    ///
+    /// Both series are aligned on their newest sample. Positive differences
+    /// grow upwards (green), negative ones downwards (red), and both halves
+    /// share one scale set by the largest absolute difference.
+    pub fn delta(top: &[f64], bottom: &[f64]) -> WaveformWidget<'static> {
+        let len = top.len().min(bottom.len());
+        let diffs = top[top.len() - len..].iter().zip(&bottom[bottom.len() - len..]).map(|(t, b)| t - b);
+        let (above, below): (Vec<f64>, Vec<f64>) = diffs.map(|d| (d.max(0.0), (-d).max(0.0))).unzip();
+        let max = peak(&above).max(peak(&below));
+
+        WaveformWidget::owned(above, below)
+            .top_style(Style::default().fg(Color::Green))
+            .bottom_style(Style::default().fg(Color::Red))
+            .top_max(max)
+            .bottom_max(max)
+    }
+
+    /// Preset for latency SLO tracking (heat-banding).
+    ///
+    /// Column height shows the latency, and each column is colored by the band
+    /// its value falls into (e.g. fast / acceptable / slow / violation). Band
+    /// boundaries are drawn as labeled markers. The scale leaves 25% headroom
+    /// above the last boundary so the violation band stays visible.
+    ///
+    /// # Example
+    /// ```rust
+    /// use waveformchart::{SloBand, WaveformWidget};
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let latency_ms = vec![40.0, 120.0, 650.0, 90.0];
+    /// let bands = [
+    ///     SloBand::new(0.0, "fast", Style::default().fg(Color::Green)),
+    ///     SloBand::new(100.0, "ok", Style::default().fg(Color::Yellow)),
+    ///     SloBand::new(300.0, "slow", Style::default().fg(Color::LightRed)),
+    ///     SloBand::new(500.0, "violation", Style::default().fg(Color::Red)),
+    /// ];
+    /// let widget = WaveformWidget::slo(&latency_ms, &bands);
+    /// ```
+    pub fn slo(latencies: &'a [f64], bands: &[SloBand<'a>]) -> Self {
+        let zones: Vec<Zone> = bands.iter().map(|b| Zone::new(b.from, b.style)).collect();
+        let markers: Vec<Marker<'a>> = bands
+            .iter()
+            .filter(|b| b.from > 0.0)
+            .map(|b| Marker::new(b.from, b.label).style(b.style))
+            .collect();
+        let last_boundary = bands.iter().map(|b| b.from).fold(0.0, f64::max);
+
+        Self::new(latencies, &[])
+            .mode(WaveformMode::HighResBraille)
+            .top_zones(&zones)
+            .top_markers(&markers)
+            .top_max(peak(latencies).max(last_boundary * 1.25))
+    }
+
+    /// Sets an optional surrounding block.
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Sets the orientation (default: horizontal). See [`Orientation`].
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Sets the scroll direction (default: left to right, newest on the right).
+    /// The fade, alignment and scroll phase follow the direction.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the baseline position (default: center). See [`Baseline`].
+    pub fn baseline(mut self, baseline: Baseline) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    /// Sets where data shorter than the chart width is placed (default: right,
+    /// so live data scrolls in from the right edge). Left alignment suits
+    /// offline recordings that are read from the start.
+    pub fn align(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Scrolls back through history: the view ends `offset` samples before
+    /// the newest one (default 0, following the tail).
+    ///
+    /// While scrolled back, a thin position indicator along the bottom edge
+    /// shows where the visible window sits within the data.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Draws the newest column in the dot column at the outer edge of its cell
+    /// (the right one, or the left one for [`Direction::RightToLeft`]), so the
+    /// newest sample touches the border instead of leaving a one-dot gap, e.g.
+    /// for charts placed side by side. Applies to the Braille and thin block
+    /// modes while no half-cell scroll phase is active. Off by default.
+    pub fn flush_edge(mut self, enable: bool) -> Self {
+        self.flush_edge = enable;
+        self
+    }
+
+    /// Zooms the time axis (default 1.0, one sample per column). Below 1.0,
+    /// `1 / zoom` samples are aggregated into each column with the
+    /// [`downsample`](Self::downsample) method (min/max by default); above 1.0,
+    /// each sample is stretched over `zoom` columns. Combine with
+    /// [`offset`](Self::offset) for pan and zoom interfaces.
+    pub fn zoom(mut self, zoom: f64) -> Self {
+        self.zoom = if zoom.is_finite() && zoom > 0.0 { zoom } else { 1.0 };
+        self
+    }
+
+    /// Renders only the samples with indices in `start..end` (clamped to the
+    /// data), as if the data were sliced to that range, e.g. to zoom into a
+    /// region without re-slicing and re-scaling the data every frame.
+    ///
+    /// The other settings apply within the window: the [`offset`](Self::offset)
+    /// scrolls back from its end, and [`fit`](Self::fit) or
+    /// [`downsample`](Self::downsample) make it fill the width.
+    pub fn window(mut self, start: usize, end: usize) -> Self {
+        self.window = Some(start..end.max(start));
+        self
+    }
+
+    /// Numbers the first sample of the top and bottom data, counting the
+    /// samples a bounded history has dropped before them (default 0). A
+    /// [`ScrollState`] then tracks the paused view and the
+    /// [`hysteresis`](Self::hysteresis) levels by sample number, so they stay
+    /// on the same samples while old ones are dropped. [`WaveformState::widget`](crate::WaveformState::widget) sets it.
+    pub fn first_sample(mut self, top: usize, bottom: usize) -> Self {
+        self.top.first = top;
+        self.bottom.first = bottom;
+        self
+    }
+
+    /// Compresses data longer than the chart width into one bar per bucket of
+    /// samples, so every sample contributes instead of only the newest ones.
+    /// With an [`offset`](Self::offset), the samples scrolled out of view past
+    /// the newest edge are left out. See [`Downsample`].
+    pub fn downsample(mut self, method: Downsample) -> Self {
+        self.downsample = Some(method);
+        self
+    }
+
+    /// Sets how data shorter than the chart width is spread over it
+    /// (default: one sample per bar). See [`Fit`].
+    pub fn fit(mut self, fit: Fit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Reserves `rows` blank rows between the top and bottom halves (default 0),
+    /// e.g. for inline labels. The axis line, if any, is drawn in the middle
+    /// of the gap.
+    pub fn center_gap(mut self, rows: u16) -> Self {
+        self.center_gap = rows;
+        self
+    }
+
+    /// Draws an axis line along the baseline in this style (default: none),
+    /// so the halves stay visually separated while values are near zero.
+    /// The waveform is drawn over the line.
+    pub fn baseline_style(mut self, style: Style) -> Self {
+        self.baseline_style = Some(style);
+        self
+    }
+
+    /// Draws an axis line along the baseline with this glyph (default `─`).
+    /// See [`baseline_style`](Self::baseline_style).
+    pub fn baseline_char(mut self, glyph: char) -> Self {
+        self.baseline_char = Some(glyph);
+        self
+    }
+
+    /// Draws a lightweight chart frame in this style (default: none): ticks in
+    /// the corners of the chart area and an arrowhead where the baseline meets
+    /// the newest edge, for a plot look without a full [`Block`] border.
+    /// The frame is drawn inside the chart area, beneath the waveform.
+    pub fn frame_style(mut self, style: Style) -> Self {
+        self.frame_style = Some(style);
+        self
+    }
+
+    /// Paints the whole chart area in this style before anything is drawn
+    /// (default: none), e.g. a subtle dark backdrop instead of whatever was in
+    /// the buffer. The waveform's styles are patched over it, so a background
+    /// color stays visible behind the glyphs.
+    pub fn background_style(mut self, style: Style) -> Self {
+        self.background_style = Some(style);
+        self
+    }
+
+    /// Paints the cells between the baseline and the waveform in this style
+    /// (default: none), filling the area under the curve. Mostly useful with a
+    /// background color, and in [`WaveformMode::BrailleLine`] where the cells
+    /// below the line are otherwise left empty.
+    pub fn under_curve_style(mut self, style: Style) -> Self {
+        self.under_curve_style = Some(style);
+        self
+    }
+
+    /// Embeds a `width` × `height` overview of the full history in the top
+    /// corner at the oldest edge, with the part currently in view (see
+    /// [`offset`](Self::offset)) highlighted in `highlight`: the classic
+    /// overview + detail pattern for long recordings.
+    ///
+    /// The overview is a bordered, downsampled copy of the chart using the
+    /// same data, styles, scales and direction.
+    pub fn overview(mut self, width: u16, height: u16, highlight: Style) -> Self {
+        self.overview = Some((width, height, highlight));
+        self
+    }
+
+    /// Gives `ratio` (0.0 - 1.0) of the height to the top half and the rest
+    /// to the bottom half, e.g. `0.7` for 70% CPU above 30% network.
+    /// Shorthand for [`Baseline::Ratio`].
+    pub fn split_ratio(self, ratio: f64) -> Self {
+        self.baseline(Baseline::Ratio(ratio))
+    }
+
+    /// Labels the baseline (`0`) and the max of each half in a gutter beside
+    /// the chart, followed by the half's unit (e.g. `"%"`, `" MB/s"`).
+    ///
+    /// Taller halves get round values in between, about one every three rows,
+    /// re-evaluated on every render so labels never overlap after a resize.
+    ///
+    /// When the units differ, the top half's labels go in a left gutter and
+    /// the bottom half's in a right gutter, so both scales stay readable.
+    /// Otherwise both share the left gutter. Vertical charts are not labeled.
+    pub fn axis_labels(mut self, top_unit: &'a str, bottom_unit: &'a str) -> Self {
+        self.axis_units = Some((top_unit, bottom_unit));
+        self
+    }
+
+    /// Formats the axis label values with `format` instead of the built-in
+    /// compact number followed by the unit, e.g. `|v| format!("{:.0}%", v * 100.0)`
+    /// for data normalized to 0.0 - 1.0. Enables the axis labels if
+    /// [`axis_labels`](Self::axis_labels) wasn't called, with both halves in
+    /// the left gutter.
+    pub fn axis_format(mut self, format: impl Fn(f64) -> String + 'a) -> Self {
+        self.axis_format = Some(Box::new(format));
+        self.axis_units.get_or_insert(("", ""));
+        self
+    }
+
+    /// Prints the newest sample of each half at the newest edge, level with
+    /// its column (e.g. `42%`), in the half's style. The value is formatted
+    /// like the axis labels: by [`axis_format`](Self::axis_format) if set,
+    /// otherwise as a compact number followed by the
+    /// [`axis_labels`](Self::axis_labels) unit.
+    pub fn show_last_value(mut self, show: bool) -> Self {
+        self.show_last_value = show;
+        self
+    }
+
+    /// Draws a vertical crosshair through the column showing the sample at
+    /// data `index`, with a callout of each half's value at that index in the
+    /// half's outer row. The value is formatted like
+    /// [`show_last_value`](Self::show_last_value). Nothing is drawn while the
+    /// index is out of view.
+    ///
+    /// Rendered as a [`StatefulWidget`](ratatui::widgets::StatefulWidget), the
+    /// [`ScrollState`] cursor is used when none is set here.
+    pub fn cursor(mut self, index: Option<usize>) -> Self {
+        self.cursor = index;
+        self
+    }
+
+    /// Style of the [`cursor`](Self::cursor) line. Defaults to gray.
+    pub fn cursor_style(mut self, style: Style) -> Self {
+        self.cursor_style = style;
+        self
+    }
+
+    /// Highlights the column under buffer cell `position`, e.g. the last
+    /// mouse position, if it holds data. See [`hovered`](Self::hovered) for
+    /// the sample there.
+    pub fn hover(mut self, position: Option<(u16, u16)>) -> Self {
+        self.hover = position;
+        self
+    }
+
+    /// Style patched over the [`hover`](Self::hover)ed column. Defaults to a
+    /// dark gray background.
+    pub fn hover_style(mut self, style: Style) -> Self {
+        self.hover_style = style;
+        self
+    }
+
+    /// Shows `aggregates` of the samples in view (e.g. [`Aggregate::MIN`],
+    /// [`Aggregate::MAX`], [`Aggregate::AVG`] or custom ones) in the outer
+    /// right corner of each half, as `name value` pairs. Halves without
+    /// samples in view show no stats.
+    pub fn stats(mut self, aggregates: &[Aggregate<'a>]) -> Self {
+        self.stats = aggregates.to_vec();
+        self
+    }
+
+    /// Shows the min, max and average of the samples in view in the outer
+    /// right corner of each half, e.g. `min 2 max 97 avg 41.5`. Shorthand for
+    /// [`stats`](Self::stats) with the built-in aggregates.
+    pub fn show_stats(self, show: bool) -> Self {
+        let aggregates = if show { &[Aggregate::MIN, Aggregate::MAX, Aggregate::AVG][..] } else { &[] };
+        self.stats(aggregates)
+    }
+
+    /// Routes all text the widget generates through `localize`, for
+    /// non-English dashboards or custom numeral systems: the equalization tag,
+    /// the built-in stat names and every number it prints (units and
+    /// [`axis_format`](Self::axis_format)ted values are left as given).
+    /// Returning `None` keeps the built-in text. See [`Label`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use waveformchart::{Label, WaveformWidget};
+    ///
+    /// let widget = WaveformWidget::new(&[0.5], &[])
+    ///     .equalize(true)
+    ///     .localize(|label| match label {
+    ///         Label::Equalized => Some("ÉG".to_string()),
+    ///         _ => None,
+    ///     });
+    /// ```
+    pub fn localize(mut self, localize: impl Fn(Label) -> Option<String> + 'a) -> Self {
+        self.localize = Some(Box::new(localize));
+        self
+    }
+
+    /// Sets how NaN and infinite samples are drawn (default: skipped, leaving
+    /// an empty column). Gaps inserted by a heartbeat are NaN samples as well.
+    /// Missing samples (`None`, see [`Sample::is_missing`]) always stay gaps.
+    pub fn invalid(mut self, invalid: Invalid) -> Self {
+        self.invalid = invalid;
+        self
+    }
+
+    /// Draws a `┊` marker next to the baseline for every gap (NaN sample),
+    /// so missing data stands out from values of zero. Off by default.
+    pub fn gap_style(mut self, style: Style) -> Self {
+        self.gap_style = Some(style);
+        self
+    }
+
+    /// Patches `style` (e.g. a red foreground) over the topmost cell of every
+    /// column whose sample (stacked layers included) exceeds the half's max,
+    /// so clipping shows at a glance instead of being silently clamped.
+    /// Off by default.
+    pub fn clip_style(mut self, style: Style) -> Self {
+        self.clip_style = Some(style);
+        self
+    }
+
+    /// Style of the gap markers after the newest real sample, flagging a
+    /// source that stopped sending. Requires [`gap_style`](Self::gap_style).
+    pub fn stale_style(mut self, style: Style) -> Self {
+        self.stale_style = Some(style);
+        self
+    }
+
+    /// Registers an observer called during render with every column drawn:
+    /// the data index, scaled value, height and final style of each half.
+    ///
+    /// Apps can feed synchronized side panels (e.g. details of the column
+    /// under the cursor) from it without repeating the scaling and offset
+    /// math. Same data as [`rendered_columns`](Self::rendered_columns); for
+    /// vertical charts, `x` counts rows from the top.
+    pub fn on_render_column(mut self, observer: impl FnMut(&RenderedColumn) + 'a) -> Self {
+        self.on_render_column = Some(Box::new(observer));
+        self
+    }
+
+    /// Text of a generated label, after the localization hook.
+    fn label(&self, label: Label) -> String {
+        self.localize.as_ref().and_then(|localize| localize(label)).unwrap_or_else(|| label.default_text())
+    }
+
+    /// Sets the rendering mode.
+    pub fn mode(mut self, mode: WaveformMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the style (color, modifier) for the top half.
+    pub fn top_style(mut self, style: Style) -> Self {
+        self.top.style = style;
+        self
+    }
+
+    /// Sets the style (color, modifier) for the bottom half.
+    pub fn bottom_style(mut self, style: Style) -> Self {
+        self.bottom.style = style;
+        self
+    }
+
+    /// Enables or disables the horizontal fade effect.
+    pub fn fade_effect(mut self, enable: bool) -> Self {
+        self.fade_effect = enable;
+        self
+    }
+
+    /// Sets where the fade starts, as the fraction of the width from the
+    /// newest edge kept at full brightness (default 0.5). `0.0` fades across
+    /// the whole chart, `1.0` doesn't fade at all.
+    pub fn fade_start(mut self, start: f64) -> Self {
+        self.fade_start = start.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets how brightness falls off towards the oldest column (default
+    /// [`FadeCurve::Linear`]).
+    pub fn fade_curve(mut self, curve: FadeCurve) -> Self {
+        self.fade_curve = curve;
+        self
+    }
+
+    /// Enables or disables the vertical gradient effect.
+    pub fn gradient_effect(mut self, enable: bool) -> Self {
+        self.gradient_effect = enable;
+        self
+    }
+
+    /// Sets which end of the columns the vertical gradient keeps brightest
+    /// (default: [`GradientDirection::BrightCenter`], dimming the peaks).
+    pub fn gradient_direction(mut self, direction: GradientDirection) -> Self {
+        self.gradient_direction = direction;
+        self
+    }
+
+    /// Sets how much the vertical gradient dims its dim end, from 0.0 (no
+    /// dimming) to 1.0 (fading to black); default 0.7.
+    pub fn gradient_strength(mut self, strength: f64) -> Self {
+        self.gradient_strength = strength.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Colors each row by its height within the half (0.0 at the baseline,
+    /// 1.0 at the outer edge), interpolating between `stops`, e.g.
+    /// `&[(0.0, Color::Green), (0.7, Color::Yellow), (1.0, Color::Red)]` for a
+    /// CPU meter. Replaces the dimming of [`gradient_effect`](Self::gradient_effect);
+    /// zones and the fade still apply on top.
+    pub fn gradient(mut self, stops: &[(f64, Color)]) -> Self {
+        self.gradient_stops = stops.to_vec();
+        self.gradient_stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self
+    }
+
+    /// Colors each row by its height through a ready-made colormap, e.g.
+    /// [`Colormap::Viridis`]; shorthand for [`gradient`](Self::gradient) with
+    /// [`Colormap::stops`].
+    pub fn colormap(self, colormap: Colormap) -> Self {
+        self.gradient(&colormap.stops())
+    }
+
+    /// Colors each whole column by its sample's value (0.0 at the bottom of the
+    /// range, 1.0 at the top), interpolating between `stops`: a heat colored
+    /// waveform where quiet columns are e.g. blue and loud ones red. Replaces
+    /// the per-row [`gradient`](Self::gradient); zones, metadata and style
+    /// callbacks are patched on top.
+    pub fn value_gradient(mut self, stops: &[(f64, Color)]) -> Self {
+        self.value_stops = stops.to_vec();
+        self.value_stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self
+    }
+
+    /// Colors each whole column by its sample's value through a ready-made
+    /// colormap; shorthand for [`value_gradient`](Self::value_gradient) with
+    /// [`Colormap::stops`].
+    pub fn value_colormap(self, colormap: Colormap) -> Self {
+        self.value_gradient(&colormap.stops())
+    }
+
+    /// Applies every setting of `theme` at once: styles, gradient stops, fade,
+    /// mode and glyph support. Builder calls after this one override it.
+    pub fn theme(mut self, theme: &WaveformTheme) -> Self {
+        self.top.style = theme.top_style;
+        self.bottom.style = theme.bottom_style;
+        self.fade_effect = theme.fade_effect;
+        self.fade_curve = theme.fade_curve;
+        self.gradient_effect = theme.gradient_effect;
+        self.gradient_direction = theme.gradient_direction;
+        self.gradient_strength = theme.gradient_strength.clamp(0.0, 1.0);
+        self.mode = theme.mode;
+        self.glyph_support = theme.glyph_support;
+        self.fade_start(theme.fade_start).gradient(&theme.gradient)
+    }
+
+    /// Sets the glyphs the target terminal can display (default: all of them).
+    ///
+    /// Without Braille, the Braille modes fall back to [`WaveformMode::SolidBlock`];
+    /// without block elements, every glyph is replaced by an ASCII stand-in.
+    /// [`GlyphSupport::detect`] guesses the support from the environment.
+    pub fn glyph_support(mut self, support: GlyphSupport) -> Self {
+        self.glyph_support = support;
+        self
+    }
+
+    /// Sets the color depth of the target terminal (default: true color).
+    ///
+    /// With [`ColorDepth::Ansi16`], colors are dimmed through the
+    /// bright/normal/dark variants of their hue (e.g. `LightGreen` → `Green` →
+    /// `DarkGray`) instead of being converted to RGB, so the terminal's own
+    /// palette is preserved; RGB colors start from their nearest named color.
+    /// Cells dimmed past the hue also get the `DIM` modifier, and undimmed
+    /// cells keep their style as given. With
+    /// [`ColorDepth::Ansi256`], the RGB colors the effects compute are
+    /// quantized to the nearest indexed color.
+    pub fn color_depth(mut self, depth: ColorDepth) -> Self {
+        self.color_depth = depth;
+        self
+    }
+
+    /// Sets the color space the fade and gradient work in (default: RGB).
+    ///
+    /// [`ColorSpace::Rgb`] scales the raw channels, which desaturates some
+    /// colors faster than others; [`ColorSpace::Hsl`] and [`ColorSpace::Oklch`]
+    /// scale the lightness instead, keeping the hue, and blend the
+    /// [`gradient`](Self::gradient) stops in the same space.
+    pub fn color_space(mut self, space: ColorSpace) -> Self {
+        self.color_space = space;
+        self
+    }
+
+    /// Enables or disables the mirror reflection effect.
+    ///
+    /// A dimmed, vertically flipped copy of the top waveform is drawn into the
+    /// bottom half (and vice versa), beneath the actual data. The reflection goes
+    /// through the same gradient and fade as the original, so it is always darker.
+    pub fn reflection_effect(mut self, enable: bool) -> Self {
+        self.reflection_effect = enable;
+        self
+    }
+
+    pub fn top_max(mut self, max: f64) -> Self {
+        self.top.max = max;
+        self
+    }
+
+    pub fn bottom_max(mut self, max: f64) -> Self {
+        self.bottom.max = max;
+        self
+    }
+
+    /// Maps `min..=max` of the top data onto the height of the half, for data
+    /// living in an arbitrary range (e.g. temperatures of 30 - 90 °C).
+    /// Values at or below `min` are drawn empty.
+    pub fn top_range(mut self, min: f64, max: f64) -> Self {
+        self.top.min = min;
+        self.top.max = max;
+        self
+    }
+
+    /// Maps `min..=max` of the bottom data onto the height of the half.
+    /// See [`top_range`](Self::top_range).
+    pub fn bottom_range(mut self, min: f64, max: f64) -> Self {
+        self.bottom.min = min;
+        self.bottom.max = max;
+        self
+    }
+
+    /// Sets the brightness of the top half (default 1.0), e.g. `0.4` to
+    /// de-emphasize a context metric without changing its color. Composes with
+    /// the fade and gradient effects.
+    pub fn top_intensity(mut self, intensity: f64) -> Self {
+        self.top.intensity = intensity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the brightness of the bottom half (default 1.0).
+    /// See [`top_intensity`](Self::top_intensity).
+    pub fn bottom_intensity(mut self, intensity: f64) -> Self {
+        self.bottom.intensity = intensity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Ignores changes of the top series smaller than `band` (in data units):
+    /// the previous drawn value is held instead, so a jittery sensor renders
+    /// as a stable line while real changes still show immediately. The band
+    /// runs from up to 4096 samples before the view, so scrolling doesn't
+    /// shift the held values; a [`ScrollState`] keeps them from frame to frame,
+    /// also while a bounded history drops old samples.
+    pub fn top_dead_band(mut self, band: f64) -> Self {
+        self.top.dead_band = band;
+        self
+    }
+
+    /// Ignores changes of the bottom series smaller than `band`.
+    /// See [`top_dead_band`](Self::top_dead_band).
+    pub fn bottom_dead_band(mut self, band: f64) -> Self {
+        self.bottom.dead_band = band;
+        self
+    }
+
+    /// Draws a faint vertical separator every `every` samples (e.g. every 60
+    /// samples = 1 minute at 1 sample/s), before each sample numbered a
+    /// multiple of `every` (see [`first_sample`](Self::first_sample)), so they
+    /// move with the data when scrolled or zoomed. Separators are drawn
+    /// beneath the data. `0` disables them.
+    pub fn separators(mut self, every: usize) -> Self {
+        self.separator_interval = (every > 0).then_some(every);
+        self
+    }
+
+    /// Highlights the columns showing samples with indices in each range, e.g.
+    /// `&[(120..180, Style::default().bg(Color::Rgb(40, 40, 60)))]` for a
+    /// selection, an anomalous period or a loop section. The style is patched
+    /// over the full height beneath the waveform, so a background tint stays
+    /// visible behind the glyphs; later regions are patched over earlier ones.
+    pub fn regions(mut self, regions: &[(Range<usize>, Style)]) -> Self {
+        self.regions = regions.to_vec();
+        self
+    }
+
+    /// Draws horizontal grid lines every `step` of each half's range (in data
+    /// units, counted from the half's min) in `style`, beneath everything
+    /// else. Vertical grid lines at time intervals are the
+    /// [`separators`](Self::separators). A `step` of 0 disables the grid;
+    /// steps finer than one row are widened to one line per row.
+    pub fn grid(mut self, step: f64, style: Style) -> Self {
+        self.grid = (step > 0.0).then_some((step, style));
+        self
+    }
+
+    /// Draws the grid lines and separators with sparse Braille dots instead of
+    /// light box drawing (default: box drawing).
+    pub fn grid_dots(mut self, dots: bool) -> Self {
+        self.grid_dots = dots;
+        self
+    }
+
+    /// Sets the style of the column separators (default: dark gray).
+    pub fn separator_style(mut self, style: Style) -> Self {
+        self.separator_style = style;
+        self
+    }
+
+    /// Sets a style for the outermost cell of each column (the waveform contour),
+    /// so the edge can stand out from the body fill. It is patched over the
+    /// gradient color; the horizontal fade still applies.
+    pub fn edge_style(mut self, style: Style) -> Self {
+        self.edge_style = Some(style);
+        self
+    }
+
+    /// Styles the highest cell of each column so peaks pop, e.g. a white tip
+    /// on a green waveform. Unlike [`edge_style`](Self::edge_style), it is
+    /// patched after the gradient and the fade, so neither dims it.
+    pub fn peak_style(mut self, style: Style) -> Self {
+        self.peak_style = Some(style);
+        self
+    }
+
+    /// Enables or disables histogram equalization of the visible window.
+    ///
+    /// Each column height becomes the rank of its value among the visible
+    /// samples, so subtle variations in a mostly flat signal spread over the
+    /// full height. This is a **nonlinear** view: heights no longer compare
+    /// across halves or frames. While enabled, an `EQ` tag is drawn in the
+    /// top-left corner so the view is not mistaken for a linear one.
+    pub fn equalize(mut self, enable: bool) -> Self {
+        self.equalize = enable;
+        self
+    }
+
+    /// Scales each half to the peak of its visible samples (stacked layers
+    /// included) plus `headroom` (e.g. `0.1` for 10%), overriding
+    /// [`top_max`](Self::top_max) and [`bottom_max`](Self::bottom_max).
+    ///
+    /// The scale follows the data in view every frame. To keep it from
+    /// jumping when a spike scrolls off, add an
+    /// [`autoscale_decay`](Self::autoscale_decay).
+    pub fn autoscale(mut self, headroom: f64) -> Self {
+        self.autoscale = Some((headroom.max(0.0), 100.0));
+        self
+    }
+
+    /// Lets the autoscaled peak fall gradually once a spike scrolls out of
+    /// view: samples before the oldest one in view still count, shrunk by
+    /// `decay` (e.g. `0.99`) per sample of distance, up to 4096 samples back.
+    /// `0.0` (the default) scales to the samples in view only.
+    pub fn autoscale_decay(mut self, decay: f64) -> Self {
+        self.autoscale_decay = decay.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Like [`autoscale`](Self::autoscale), but scales to the `percentile`
+    /// (0 - 100, e.g. `99.0`) of the visible values instead of their peak, so
+    /// a single outlier doesn't flatten the rest of the chart. Samples above
+    /// it are clamped to the full height.
+    pub fn autoscale_percentile(mut self, percentile: f64, headroom: f64) -> Self {
+        self.autoscale = Some((headroom.max(0.0), percentile.clamp(0.0, 100.0)));
+        self
+    }
+
+    /// Draws a min-hold trace over the top half: a floor that drops to every
+    /// new low and rises by `rise` (a fraction of the half) per column after
+    /// it, so the headroom between the floor and the peaks stays visible.
+    pub fn top_min_hold(mut self, rise: f64, style: Style) -> Self {
+        self.top.min_hold = Some((rise.max(0.0), style));
+        self
+    }
+
+    /// Draws a min-hold trace over the bottom half. See [`top_min_hold`](Self::top_min_hold).
+    pub fn bottom_min_hold(mut self, rise: f64, style: Style) -> Self {
+        self.bottom.min_hold = Some((rise.max(0.0), style));
+        self
+    }
+
+    /// Draws a peak-hold line across the top half at `level` (same scale as
+    /// the data), beneath the waveform. The held level is usually tracked
+    /// across frames by [`WaveformState::peak_hold`](crate::WaveformState::peak_hold).
+    pub fn top_peak_hold(mut self, level: f64, style: Style) -> Self {
+        self.top.peak_hold = Some((level, style));
+        self
+    }
+
+    /// Draws a peak-hold line across the bottom half. See [`top_peak_hold`](Self::top_peak_hold).
+    pub fn bottom_peak_hold(mut self, level: f64, style: Style) -> Self {
+        self.bottom.peak_hold = Some((level, style));
+        self
+    }
+
+    /// Sets the time of the frame being drawn (e.g. the time since the app
+    /// started), which drives every time-based effect such as the burn-in
+    /// protection.
+    ///
+    /// The widget never reads the clock itself, so the same inputs always
+    /// render the same frame, e.g. in golden tests.
+    pub fn frame_time(mut self, time: Duration) -> Self {
+        self.frame_time = time;
+        self
+    }
+
+    /// Enables burn-in protection for displays left running for days.
+    ///
+    /// Every five minutes of [`frame_time`](Self::frame_time) the whole chart
+    /// (border, baseline and all) moves by one cell, circling through four
+    /// positions, so no cell shows the same static glyph for long.
+    /// The chart gives up one column and one row to make room for the shift.
+    pub fn burn_in_protection(mut self, enabled: bool) -> Self {
+        self.burn_in = enabled;
+        self
+    }
+
+    /// Stops single-dot flicker of samples hovering at a quantization
+    /// boundary: a sample keeps the height it was drawn with in the last
+    /// frame until it moves by more than half a dot past the boundary.
+    ///
+    /// Needs the previous frame, so it only applies when rendered as a
+    /// [`StatefulWidget`](ratatui::widgets::StatefulWidget) with a
+    /// [`ScrollState`]. Samples are matched by data index. Density mode,
+    /// whose shades are not dot heights, is unaffected.
+    pub fn hysteresis(mut self, enable: bool) -> Self {
+        self.hysteresis = enable;
+        self
+    }
+
+    /// Renders the waveform cells through a per-frame style palette: each
+    /// distinct cell style (after zones, gradient and fade) is computed once,
+    /// cells record a palette index, and the styles are written at the end,
+    /// one write per run of cells sharing a style. Cuts the style writes of very
+    /// wide charts, whose columns mostly share a few styles. The output is
+    /// identical either way.
+    pub fn style_palette(mut self, enable: bool) -> Self {
+        self.style_palette = enable;
+        self
+    }
+
+    /// Sets how values map to column heights (default: linear), e.g.
+    /// `Scale::Db { floor: -60.0 }` for audio meters, where quiet signals
+    /// would be invisible on a linear scale. Markers follow the same scale.
+    pub fn scale(mut self, scale: Scale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Compresses values above `threshold` (a fraction of the half's max) by
+    /// `ratio` instead of hard-clamping them flat at the max, blending in over
+    /// a soft knee `knee` wide (also a fraction of the max). Occasional
+    /// overshoots then still show their shape; only overshoots past the
+    /// compressed range are clamped.
+    ///
+    /// `soft_clip(0.8, 0.2, 4.0)` is a good start for audio meters.
+    pub fn soft_clip(mut self, threshold: f64, knee: f64, ratio: f64) -> Self {
+        self.soft_clip = Some(SoftClip { threshold, knee: knee.max(0.0), ratio: ratio.max(1.0) });
+        self
+    }
+
+    /// Sets how many cells each sample occupies horizontally (default 1).
+    pub fn bar_width(mut self, width: usize) -> Self {
+        self.bar_width = width.max(1);
+        self
+    }
+
+    /// Sets the number of blank cells between consecutive bars (default 0).
+    /// Combined with [`bar_width`](Self::bar_width) this gives a bar-graph look.
+    pub fn bar_gap(mut self, gap: usize) -> Self {
+        self.bar_gap = gap;
+        self
+    }
+
+    /// Sets a fractional horizontal scroll offset, in cells (0.0 - 1.0).
+    ///
+    /// Feed it the time elapsed since the newest sample arrived, relative to the
+    /// sample interval, so samples slide in dot by dot instead of jumping a whole
+    /// cell per tick. Braille and thin block modes have two sub-columns per cell,
+    /// so from a phase of 0.5 every column moves half a cell to the left.
+    /// Solid block and density modes fill whole cells and ignore the phase.
+    pub fn scroll_phase(mut self, phase: f64) -> Self {
+        self.scroll_phase = phase.rem_euclid(1.0);
+        self
+    }
+
+    /// Sets labeled baselines for the top half. See [`Marker`].
+    pub fn top_markers(mut self, markers: &[Marker<'a>]) -> Self {
+        self.top.markers = markers.to_vec();
+        self
+    }
+
+    /// Sets labeled baselines for the bottom half. See [`Marker`].
+    pub fn bottom_markers(mut self, markers: &[Marker<'a>]) -> Self {
+        self.bottom.markers = markers.to_vec();
+        self
+    }
+
+    /// Draws labeled horizontal lines across both halves at specific values,
+    /// e.g. `&[(0.5, Style::default().fg(Color::Red), Some("-6 dB"))]`, beneath
+    /// the waveform. Values use the units of each half; for halves on different
+    /// scales, set them per half with [`top_reference_lines`](Self::top_reference_lines)
+    /// and [`bottom_reference_lines`](Self::bottom_reference_lines).
+    pub fn reference_lines(self, lines: &[(f64, Style, Option<&'a str>)]) -> Self {
+        self.top_reference_lines(lines).bottom_reference_lines(lines)
+    }
+
+    /// Draws labeled horizontal lines across the top half at specific values,
+    /// e.g. `&[(80.0, Style::default().fg(Color::Red), Some("80% CPU"))]`,
+    /// beneath the waveform. Shorthand for [`top_markers`](Self::top_markers).
+    pub fn top_reference_lines(self, lines: &[(f64, Style, Option<&'a str>)]) -> Self {
+        let markers = reference_markers(lines);
+        self.top_markers(&markers)
+    }
+
+    /// Draws labeled horizontal lines across the bottom half at specific values.
+    /// See [`top_reference_lines`](Self::top_reference_lines).
+    pub fn bottom_reference_lines(self, lines: &[(f64, Style, Option<&'a str>)]) -> Self {
+        let markers = reference_markers(lines);
+        self.bottom_markers(&markers)
+    }
+
+    /// Attaches per-sample metadata to the top half.
+    ///
+    /// `metadata[i]` belongs to the `i`-th data sample. The `style` callback maps
+    /// a metadata entry (HTTP status, note pitch, log level, ...) to a style that
+    /// is patched over the series and zone styles of that column, before the
+    /// gradient and fade are applied. Returning `None` keeps the column unchanged.
+    ///
+    /// # Example
+    /// ```rust
+    /// use waveformchart::WaveformWidget;
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let latency = vec![0.2, 0.3, 0.9];
+    /// let status = vec![200, 200, 503];
+    /// let widget = WaveformWidget::new(&latency, &[])
+    ///     .top_metadata(&status, |code| (*code >= 500).then(|| Style::default().fg(Color::Red)));
+    /// ```
+    pub fn top_metadata<M>(mut self, metadata: &'a [M], style: impl Fn(&M) -> Option<Style> + 'a) -> Self {
+        self.top.metadata = Some(Box::new(move |i| metadata.get(i).and_then(&style)));
+        self
+    }
+
+    /// Attaches per-sample metadata to the bottom half.
+    /// See [`top_metadata`](Self::top_metadata).
+    pub fn bottom_metadata<M>(mut self, metadata: &'a [M], style: impl Fn(&M) -> Option<Style> + 'a) -> Self {
+        self.bottom.metadata = Some(Box::new(move |i| metadata.get(i).and_then(&style)));
+        self
+    }
+
+    /// Computes a style for each top column from its sample's data index and
+    /// value (e.g. red for error values, or a highlight for the sample under
+    /// the cursor). The style is patched over the series, zone and metadata
+    /// styles, and the gradient and fade still apply on top.
+    ///
+    /// # Example
+    /// ```rust
+    /// use waveformchart::WaveformWidget;
+    /// use ratatui::style::{Color, Style};
+    ///
+    /// let cursor = 2;
+    /// let widget = WaveformWidget::new(&[0.2, 0.3, 0.9], &[]).top_style_fn(move |index, value| match index {
+    ///     i if i == cursor => Style::default().fg(Color::White),
+    ///     _ if value > 0.8 => Style::default().fg(Color::Red),
+    ///     _ => Style::default(),
+    /// });
+    /// ```
+    pub fn top_style_fn(mut self, style: impl Fn(usize, f64) -> Style + 'a) -> Self {
+        self.top.style_fn = Some(Box::new(style));
+        self
+    }
+
+    /// Computes a style for each bottom column from its sample's data index
+    /// and value. See [`top_style_fn`](Self::top_style_fn).
+    pub fn bottom_style_fn(mut self, style: impl Fn(usize, f64) -> Style + 'a) -> Self {
+        self.bottom.style_fn = Some(Box::new(style));
+        self
+    }
+
+    /// Sets the value zones for the top half. See [`Zone`].
+    pub fn top_zones(mut self, zones: &[Zone]) -> Self {
+        self.top.zones = sorted_zones(zones);
+        self
+    }
+
+    /// Sets the value zones for the bottom half. See [`Zone`].
+    pub fn bottom_zones(mut self, zones: &[Zone]) -> Self {
+        self.bottom.zones = sorted_zones(zones);
+        self
+    }
+
+    /// Styles top columns whose value reaches a level, e.g.
+    /// `&[(0.8, Style::default().fg(Color::Red))]` for CPU above 80%.
+    /// Levels use the same units as the data. The highest level reached wins.
+    /// Shorthand for [`Zone::new`] zones.
+    pub fn top_thresholds(self, thresholds: &[(f64, Style)]) -> Self {
+        let zones: Vec<Zone> = thresholds.iter().map(|&(level, style)| Zone::new(level, style)).collect();
+        self.top_zones(&zones)
+    }
+
+    /// Styles bottom columns whose value reaches a level.
+    /// See [`top_thresholds`](Self::top_thresholds).
+    pub fn bottom_thresholds(self, thresholds: &[(f64, Style)]) -> Self {
+        let zones: Vec<Zone> = thresholds.iter().map(|&(level, style)| Zone::new(level, style)).collect();
+        self.bottom_zones(&zones)
+    }
+
+    /// Stacks more series on top of the top half's data, bottom-up, each in
+    /// its own style (e.g. per-process CPU usage). Layers are indexed like
+    /// the data and share its scale; see [`stacking`](Self::stacking).
+    pub fn top_layers(mut self, layers: &[(&'a [f64], Style)]) -> Self {
+        self.top.layers = layers.to_vec();
+        self
+    }
+
+    /// Stacks more series below the bottom half's data.
+    /// See [`top_layers`](Self::top_layers).
+    pub fn bottom_layers(mut self, layers: &[(&'a [f64], Style)]) -> Self {
+        self.bottom.layers = layers.to_vec();
+        self
+    }
+
+    /// Sets how stacked layers are scaled (default: absolute). See [`Stacking`].
+    pub fn stacking(mut self, stacking: Stacking) -> Self {
+        self.stacking = stacking;
+        self
+    }
+
+    /// Attaches ascending timestamps (any unit) to the top half's samples, for
+    /// irregular, event-driven sources such as state-change streams.
+    ///
+    /// The time range of the data is then split into one equal-length bucket
+    /// per bar, and each bar shows the time-weighted mean of its bucket: every
+    /// sample holds its value until the next timestamp, so long-lasting values
+    /// count more than momentary blips. The newest sample holds for the average
+    /// sample interval, and NaN or infinite samples are left out of the mean.
+    pub fn top_timestamps(mut self, timestamps: &'a [f64]) -> Self {
+        self.top.timestamps = Some(timestamps);
+        self
+    }
+
+    /// Attaches timestamps to the bottom half's samples.
+    /// See [`top_timestamps`](Self::top_timestamps).
+    pub fn bottom_timestamps(mut self, timestamps: &'a [f64]) -> Self {
+        self.bottom.timestamps = Some(timestamps);
+        self
+    }
+
+    /// Draws a reference series (yesterday's traffic, a baseline run, ...)
+    /// beneath the top half's data, scaled by the same `top_max`.
+    ///
+    /// The underlay uses the series style but only every other Braille dot, in
+    /// a checkerboard over both dot columns, so it reads as a lighter shade of
+    /// the data even on terminals that can't dim colors. Where the data is
+    /// taller than the underlay, the data covers it.
+    pub fn top_underlay(mut self, data: &'a [f64]) -> Self {
+        self.top.underlay = Some(data);
+        self
+    }
+
+    /// Draws a reference series beneath the bottom half's data.
+    /// See [`top_underlay`](Self::top_underlay).
+    pub fn bottom_underlay(mut self, data: &'a [f64]) -> Self {
+        self.bottom.underlay = Some(data);
+        self
+    }
+}
+
+impl<'a> WaveformWidget<'a> {
+    /// Renders the widget into an owned `Text` of `width` x `height` cells.
+    ///
+    /// Useful for embedding a small sparkline as a cell value in a ratatui
+    /// `Table` or `List` row instead of rendering a standalone widget.
+    /// Consecutive cells with the same style are merged into one `Span`.
+    pub fn to_text(self, width: u16, height: u16) -> Text<'static> {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        self.render(area, &mut buf);
+
+        let lines: Vec<Line<'static>> = (0..height)
+            .map(|y| {
+                let mut spans: Vec<Span<'static>> = Vec::new();
+                for x in 0..width {
+                    let cell = &buf[(x, y)];
+                    match spans.last_mut() {
+                        Some(span) if span.style == cell.style() => {
+                            span.content.to_mut().push_str(cell.symbol());
+                        }
+                        _ => spans.push(Span::styled(cell.symbol().to_string(), cell.style())),
+                    }
+                }
+                Line::from(spans)
+            })
+            .collect();
+
+        Text::from(lines)
+    }
+}
+
+impl<'a> WaveformWidget<'a> {
+    /// Returns the final style of the newest (rightmost) column of the top and
+    /// bottom halves when rendered into `area`, after zones and gradient.
+    ///
+    /// The style is the one of the cell next to the center line, so apps can
+    /// color status-bar labels to match exactly what the chart shows.
+    /// A half without data returns `None`.
+    pub fn newest_styles(&self, area: Rect) -> (Option<Style>, Option<Style>) {
+        let frame = self.frame(area);
+        let width = frame.chart.width as usize;
+        let capacity = self.bar_capacity(width);
+        let (top_half, bottom_half) = self.halves(frame.chart);
+
+        let newest = |series: &Series, prepared: &Prepared, half: Half| {
+            let last = prepared.values.len().checked_sub(1)?;
+            let (extent, style) = prepared.sample(last);
+            let is_tip = self.column_glyphs(extent, half).len() <= 1;
+            // Faded like the column drawn furthest towards the newest edge.
+            let drawn = |relative_x| {
+                let slot = self.bar_slot(relative_x, width)?;
+                sample_index(self.alignment, prepared.values.len(), capacity, slot)
+            };
+            let newest_x = (0..width).rev().find(|&x| drawn(x) == Some(last));
+            let fade_factor = newest_x.map_or(1.0, |x| self.fade_factor(x, width));
+            Some(self.cell_style(style, 0, half.rows, is_tip, fade_factor * series.intensity))
+        };
+
+        (
+            newest(&self.top, &frame.top, top_half),
+            newest(&self.bottom, &frame.bottom, bottom_half),
+        )
+    }
+}
+
+/// Half of the chart a [`Hit`] lies in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Top,
+    Bottom,
+}
+
+/// Sample under a buffer cell, as returned by [`WaveformWidget::hit_test`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    /// Half the cell lies in.
+    pub series: Side,
+    /// Index of the data sample drawn in the column (the closest one when resampled).
+    pub index: usize,
+    /// Raw value of that sample, before scaling.
+    pub value: f64,
+}
+
+/// What the widget draws in one column, as returned by
+/// [`WaveformWidget::rendered_columns`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedColumn {
+    /// Buffer column the data is drawn in.
+    pub x: u16,
+    /// Top half sample, `None` if the column is blank there.
+    pub top: Option<RenderedSample>,
+    /// Bottom half sample, `None` if the column is blank there.
+    pub bottom: Option<RenderedSample>,
+}
+
+/// One half of a [`RenderedColumn`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderedSample {
+    /// Index of the data sample drawn (the closest one when resampled).
+    pub index: usize,
+    /// Value after scaling and equalization (0.0 - 1.0).
+    pub value: f64,
+    /// Height covered by the column, in dot rows (4 per cell).
+    pub dots: u16,
+    /// Final style of the outermost cell, after zones, gradient and fade.
+    pub style: Style,
+}
+
+impl<'a> WaveformWidget<'a> {
+    /// Returns, for every column holding data when rendered into `area`, the
+    /// values, heights and styles the renderer uses.
+    ///
+    /// Apps can use it to line custom overlays up with the chart, or to check
+    /// the renderer's math without parsing the buffer.
+    pub fn rendered_columns(&self, area: Rect) -> Vec<RenderedColumn> {
+        let frame = self.frame(area);
+        self.columns_of(frame.chart, &frame.top, &frame.bottom)
+    }
+
+    /// Finds the sample drawn at buffer cell `position` when the widget is
+    /// rendered into `area`: the half it lies in, the column's data index and
+    /// the raw value there. Alignment, bar layout, windowing, zoom, offset and
+    /// orientation are accounted for. Returns `None` outside the chart, in the
+    /// center gap or over columns without data.
+    ///
+    /// When rendering as a [`StatefulWidget`](ratatui::widgets::StatefulWidget),
+    /// use [`hit_test_with`](Self::hit_test_with) instead.
+    pub fn hit_test(&self, area: Rect, position: (u16, u16)) -> Option<Hit> {
+        self.hit_in(&self.frame(self.burn_in_area(area)), position)
+    }
+
+    /// [`hit_test`](Self::hit_test) within an already prepared frame.
+    fn hit_in(&self, frame: &Frame, position: (u16, u16)) -> Option<Hit> {
+        let inner_area = frame.inner_area;
+        let (x, y) = position;
+        if !inner_area.contains(Position::new(x, y)) {
+            return None;
+        }
+        // Vertical charts are drawn horizontally, then turned a quarter clockwise.
+        let (inner_area, x, y) = match self.orientation {
+            Orientation::Horizontal => (inner_area, x, y),
+            Orientation::Vertical => {
+                let logical = Rect::new(0, 0, inner_area.height, inner_area.width);
+                (logical, y - inner_area.top(), inner_area.right() - 1 - x)
+            }
+        };
+
+        let (top_half, bottom_half) = self.halves(inner_area);
+        let covers = |half: Half| half.rows > 0 && (0..half.rows).any(|i| half.row(i) == y);
+        let series = if covers(top_half) {
+            Side::Top
+        } else if covers(bottom_half) {
+            Side::Bottom
+        } else {
+            return None;
+        };
+        let column = self.columns_of(inner_area, &frame.top, &frame.bottom).into_iter().find(|column| column.x == x)?;
+        let (sample, data) = match series {
+            Side::Top => (column.top?, &self.top.data),
+            Side::Bottom => (column.bottom?, &self.bottom.data),
+        };
+        let value = data.values(sample.index..sample.index + 1)[0];
+        Some(Hit { series, index: sample.index, value })
+    }
+
+    /// Like [`hit_test`](Self::hit_test), for a widget rendered as a
+    /// [`StatefulWidget`](ratatui::widgets::StatefulWidget) with `state`:
+    /// the offset and zoom factor of the last render are applied first.
+    pub fn hit_test_with(&self, state: &ScrollState, area: Rect, position: (u16, u16)) -> Option<Hit> {
+        let pan = Pan { zoom: self.zoom * state.zoom_factor(), offset: state.offset() };
+        self.hit_in(&self.frame_at(self.burn_in_area(area), pan), position)
+    }
+
+    /// Sample under the [`hover`](Self::hover) position when rendered into
+    /// `area`, as found by [`hit_test`](Self::hit_test).
+    pub fn hovered(&self, area: Rect) -> Option<Hit> {
+        self.hit_test(area, self.hover?)
+    }
+
+    /// Columns drawn into `inner_area` from the already prepared halves.
+    fn columns_of(&self, inner_area: Rect, top: &Prepared, bottom: &Prepared) -> Vec<RenderedColumn> {
+        let width = inner_area.width as usize;
+        let capacity = self.bar_capacity(width);
+        let (top_half, bottom_half) = self.halves(inner_area);
+
+        let sample = |prepared: &Prepared, half: Half, slot: usize, fade_factor: f64| {
+            let i = sample_index(self.alignment, prepared.values.len(), capacity, slot)?;
+            let (extent, style) = prepared.sample(i);
+            let glyphs = self.column_glyphs(extent, half);
+            let dots = glyphs.iter().flatten().map(|&g| glyph_dots(g)).sum();
+            let row = glyphs.len().saturating_sub(1) as u16;
+            let style = self.cell_style(style, row, half.rows, true, fade_factor);
+            Some(RenderedSample { index: prepared.indices[i], value: prepared.values[i], dots, style })
+        };
+
+        (0..width)
+            .filter(|&relative_x| !(self.half_cell_shift() && relative_x == 0))
+            .filter_map(|relative_x| {
+                let slot = self.bar_slot(relative_x, width)?;
+                let fade_factor = self.fade_factor(relative_x, width);
+                let x = self.shifted_x(self.column_x(inner_area, relative_x));
+                let top = sample(top, top_half, slot, fade_factor * self.top.intensity);
+                let bottom = sample(bottom, bottom_half, slot, fade_factor * self.bottom.intensity);
+                (top.is_some() || bottom.is_some()).then_some(RenderedColumn { x, top, bottom })
+            })
+            .collect()
+    }
+}
+
+/// Renders with a [`ScrollState`], which sets the [`offset`](WaveformWidget::offset)
+/// so the chart can be panned back while new data accumulates, and multiplies
+/// the [`zoom`](WaveformWidget::zoom) by its [`zoom_factor`](ScrollState::zoom_factor).
+impl<'a> ratatui::widgets::StatefulWidget for WaveformWidget<'a> {
+    type State = ScrollState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut ScrollState) {
+        let zoom = self.zoom * state.zoom_factor();
+        let widget = self.zoom(zoom);
+        let inner = widget.inner_area(widget.burn_in_area(area));
+        let width = match widget.orientation {
+            Orientation::Horizontal => inner.width,
+            Orientation::Vertical => inner.height,
+        };
+        let capacity = widget.bar_capacity(width as usize);
+        let span = widget.span(capacity);
+        let offset = state.update(widget.total_len(), span, widget.end_sample());
+        let reversed = widget.direction == Direction::RightToLeft;
+        let newest = match (widget.orientation, reversed) {
+            (Orientation::Horizontal, false) => inner.right().saturating_sub(1),
+            (Orientation::Horizontal, true) => inner.left(),
+            (Orientation::Vertical, false) => inner.bottom().saturating_sub(1),
+            (Orientation::Vertical, true) => inner.top(),
+        };
+        let pitch = widget.bar_width + widget.bar_gap;
+        state.viewport = Some(Viewport { newest, reversed, pitch, capacity, span });
+        let mut widget = widget.offset(offset);
+        widget.cursor = widget.cursor.or(state.cursor);
+        widget.hold_dead_bands(inner, &mut state.dead_bands);
+        if widget.hysteresis {
+            widget.hold_levels(inner, &mut state.levels);
+        }
+        Widget::render(widget, area, buf);
+    }
+}
+
+impl<'a> Widget for WaveformWidget<'a> {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        let support = self.glyph_support;
+        if !support.braille && matches!(self.mode, WaveformMode::HighResBraille | WaveformMode::BrailleLine) {
+            self.mode = WaveformMode::SolidBlock;
+        }
+        // Substituted once the whole chart is drawn (and rotated).
+        self.glyph_support = GlyphSupport::FULL;
+        let chart_area = self.burn_in_area(area);
+        let frame = self.frame(chart_area);
+        let hovered = self.hover.filter(|&position| self.hit_in(&frame, position).is_some()).map(|(x, y)| {
+            let inner = frame.inner_area;
+            match self.orientation {
+                Orientation::Horizontal => Rect::new(x, inner.y, 1, inner.height),
+                Orientation::Vertical => Rect::new(inner.x, y, inner.width, 1),
+            }
+        });
+        let hover_style = self.hover_style;
+        self.render_chart(chart_area, frame, buf);
+        if let Some(strip) = hovered {
+            buf.set_style(strip, hover_style);
+        }
+
+        if support != GlyphSupport::FULL {
+            for position in area.positions() {
+                let cell = &mut buf[position];
+                let Some(glyph) = cell.symbol().chars().next() else {
+                    continue;
+                };
+                let braille = ('\u{2800}'..='\u{28FF}').contains(&glyph);
+                if (braille && !support.braille) || (!glyph.is_ascii() && !support.blocks) {
+                    cell.set_char(glyphs::ascii(glyph));
+                }
+            }
+        }
+    }
+}
+
+impl<'a> WaveformWidget<'a> {
+    fn render_chart(mut self, area: Rect, frame: Frame, buf: &mut Buffer) {
+        if let Some(b) = &self.block {
+            b.render(area, buf);
+        }
+        let Frame { inner_area, chart, top, bottom } = frame;
+
+        if self.orientation == Orientation::Vertical {
+            self.block = None;
+            self.axis_units = None;
+            self.orientation = Orientation::Horizontal;
+            self.eighths = true;
+            render_rotated(self, inner_area, Frame { inner_area: chart, chart, top, bottom }, buf);
+            return;
+        }
+
+        if inner_area.height < 1 || inner_area.width < 1 {
+            return;
+        }
+
+        if let Some(style) = self.background_style {
+            buf.set_style(inner_area, style);
+        }
+
+        // Separators go first so the waveform is drawn on top of them.
+        if let Some(every) = self.separator_interval {
+            let glyph = if self.grid_dots { '⠅' } else { '│' };
+            let width = inner_area.width as usize;
+            let capacity = self.bar_capacity(width);
+            // Sample number drawn in a column (top, else bottom), like the regions.
+            let number = |relative_x| {
+                let slot = self.bar_slot(relative_x, width)?;
+                [(&top, &self.top), (&bottom, &self.bottom)].into_iter().find_map(|(prepared, series)| {
+                    let i = sample_index(self.alignment, prepared.indices.len(), capacity, slot)?;
+                    Some(series.first + prepared.indices[i])
+                })
+            };
+            // At the first column of each block of `every` samples but the oldest one in view.
+            let mut previous = None;
+            for relative_x in 0..width {
+                let Some(block) = number(relative_x).map(|n| n / every) else {
+                    continue;
+                };
+                if previous.is_some_and(|previous| previous != block) {
+                    let x = self.column_x(inner_area, relative_x);
+                    for y in inner_area.top()..inner_area.bottom() {
+                        buf[(x, y)].set_char(glyph).set_style(self.separator_style);
+                    }
+                }
+                previous = Some(block);
+            }
+        }
+
+        let (top_half, bottom_half) = self.halves(inner_area);
+
+        // So does the axis line.
+        if (self.baseline_style.is_some() || self.baseline_char.is_some())
+            && let Some(y) = self.axis_row(inner_area, top_half, bottom_half)
+        {
+            let style = self.baseline_style.unwrap_or(Style::default().fg(Color::DarkGray));
+            for x in inner_area.left()..inner_area.right() {
+                buf[(x, y)].set_char(self.baseline_char.unwrap_or('─')).set_style(style);
+            }
+        }
+
+        if let Some(style) = self.frame_style {
+            self.render_frame(inner_area, top_half, bottom_half, style, buf);
+        }
+
+        let width = inner_area.width as usize;
+        let capacity = self.bar_capacity(width);
+        if self.flush_edge && !self.half_cell_shift() {
+            self.edge_x = Some(self.column_x(inner_area, width - 1));
+        }
+
+        // Grid lines fill the cells still blank, beneath everything drawn so far.
+        if let Some((step, style)) = self.grid {
+            let glyph = if self.grid_dots { '⠒' } else { '┈' };
+            for (half, (min, max)) in [(top_half, (top.min, top.max)), (bottom_half, (bottom.min, bottom.max))] {
+                // At most one line per row, so tiny steps can't stall the loop.
+                let step = step.max((max - min) / half.rows as f64);
+                let values = (1..=half.rows).map(|i| min + i as f64 * step).take_while(|&value| value < max);
+                for value in values {
+                    let y = half.row(marker_row(half, self.curve(range_fraction(value, min, max))));
+                    for x in inner_area.left()..inner_area.right() {
+                        if buf[(x, y)].symbol() == " " {
+                            buf[(x, y)].set_char(glyph).set_style(style);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Peak-hold lines go beneath the waveform as well.
+        for (half, prepared, series) in [(top_half, &top, &self.top), (bottom_half, &bottom, &self.bottom)] {
+            if let Some((level, style)) = series.peak_hold
+                && level.is_finite()
+                && half.rows > 0
+            {
+                let y = half.row(marker_row(half, self.curve(range_fraction(level, prepared.min, prepared.max))));
+                for x in inner_area.left()..inner_area.right() {
+                    buf[(x, y)].set_char('─').set_style(style);
+                }
+            }
+        }
+
+        // Marker lines go beneath the waveform as well.
+        let markers = [
+            (top_half, (top.min, top.max), &self.top.markers),
+            (bottom_half, (bottom.min, bottom.max), &self.bottom.markers),
+        ];
+        for (half, (min, max), markers) in markers {
+            for marker in markers.iter().filter(|_| half.rows > 0) {
+                let y = half.row(marker_row(half, self.curve(range_fraction(marker.value, min, max))));
+                for x in inner_area.left()..inner_area.right() {
+                    buf[(x, y)].set_char('╌').set_style(marker.style);
+                }
+            }
+        }
+
+        if self.style_palette {
+            self.palette = Some(RefCell::new(Palette::new(inner_area)));
+        }
+        for relative_x in 0..width {
+            let x = self.column_x(inner_area, relative_x);
+            let Some(slot) = self.bar_slot(relative_x, width) else {
+                continue;
+            };
+            // The oldest column scrolls out of view.
+            if self.half_cell_shift() && relative_x == 0 {
+                continue;
+            }
+
+            // Each half is aligned on its own length, so a shorter (or empty)
+            // series simply leaves the rest of its half blank.
+            let top_sample = sample_index(self.alignment, top.values.len(), capacity, slot).map(|i| top.sample(i));
+            let bottom_sample =
+                sample_index(self.alignment, bottom.values.len(), capacity, slot).map(|i| bottom.sample(i));
+            let top_underlay = sample_index(self.alignment, top.underlay.len(), capacity, slot).map(|i| top.underlay[i]);
+            let bottom_underlay =
+                sample_index(self.alignment, bottom.underlay.len(), capacity, slot).map(|i| bottom.underlay[i]);
+
+            if top_sample.is_none() && bottom_sample.is_none() && top_underlay.is_none() && bottom_underlay.is_none() {
+                continue;
+            }
+
+            let fade_factor = self.fade_factor(relative_x, width);
+            let (top_fade, bottom_fade) = (fade_factor * self.top.intensity, fade_factor * self.bottom.intensity);
+
+            // Regions tint the column by the index of its (top, else bottom) sample.
+            let index = [&top, &bottom]
+                .into_iter()
+                .find_map(|p| sample_index(self.alignment, p.indices.len(), capacity, slot).map(|i| p.indices[i]));
+            if let Some(index) = index {
+                for (_, style) in self.regions.iter().filter(|(range, _)| range.contains(&index)) {
+                    for y in inner_area.top()..inner_area.bottom() {
+                        self.settle(buf, (self.shifted_x(x), y));
+                        buf[(self.shifted_x(x), y)].set_style(*style);
+                    }
+                }
+            }
+
+            // Reflections first, so the actual data is drawn over them.
+            if self.reflection_effect {
+                if let Some((extent, style)) = top_sample {
+                    self.render_column(buf, x, bottom_half, (extent, style), top_fade * REFLECTION_BRIGHTNESS, false);
+                }
+                if let Some((extent, style)) = bottom_sample {
+                    self.render_column(buf, x, top_half, (extent, style), bottom_fade * REFLECTION_BRIGHTNESS, false);
+                }
+            }
+
+            if let Some(val) = top_underlay {
+                self.render_underlay(buf, x, top_half, val, self.top.style, top_fade);
+            }
+            if let Some(val) = bottom_underlay {
+                self.render_underlay(buf, x, bottom_half, val, self.bottom.style, bottom_fade);
+            }
+
+            if let Some(style) = self.under_curve_style {
+                for (sample, half) in [(top_sample, top_half), (bottom_sample, bottom_half)] {
+                    if let Some((extent, _)) = sample {
+                        self.render_under_curve(buf, x, half, extent, style);
+                    }
+                }
+            }
+
+            if let Some((extent, style)) = top_sample {
+                self.render_column(buf, x, top_half, (extent, style), top_fade, false);
+            }
+            if let Some((extent, style)) = bottom_sample {
+                self.render_column(buf, x, bottom_half, (extent, style), bottom_fade, false);
+            }
+            for (prepared, half, fade) in [(&top, top_half, top_fade), (&bottom, bottom_half, bottom_fade)] {
+                if let Some(i) = sample_index(self.alignment, prepared.values.len(), capacity, slot) {
+                    self.render_stack(buf, x, half, prepared, i, fade);
+                }
+            }
+
+            if let Some(gap_style) = self.gap_style {
+                for (prepared, half) in [(&top, top_half), (&bottom, bottom_half)] {
+                    let Some(i) = sample_index(self.alignment, prepared.values.len(), capacity, slot) else {
+                        continue;
+                    };
+                    if prepared.gaps[i] && half.rows > 0 {
+                        let trailing = prepared.last_sample.is_none_or(|last| i > last);
+                        let style = if trailing { self.stale_style.unwrap_or(gap_style) } else { gap_style };
+                        self.settle(buf, (self.shifted_x(x), half.row(0)));
+                        buf[(self.shifted_x(x), half.row(0))].set_char('┊').set_style(style);
+                    }
+                }
+            }
+
+            if let Some(clip_style) = self.clip_style {
+                for (prepared, half) in [(&top, top_half), (&bottom, bottom_half)] {
+                    let Some(i) = sample_index(self.alignment, prepared.values.len(), capacity, slot) else {
+                        continue;
+                    };
+                    if prepared.clipped[i] && half.rows > 0 {
+                        self.settle(buf, (self.shifted_x(x), half.row(half.rows - 1)));
+                        buf[(self.shifted_x(x), half.row(half.rows - 1))].set_style(clip_style);
+                    }
+                }
+            }
+
+            for (prepared, series, half) in [(&top, &self.top, top_half), (&bottom, &self.bottom, bottom_half)] {
+                let Some((_, style)) = series.min_hold else {
+                    continue;
+                };
+                let hold = sample_index(self.alignment, prepared.min_hold.len(), capacity, slot)
+                    .map(|i| prepared.min_hold[i])
+                    .filter(|hold| hold.is_finite());
+                if let Some(hold) = hold
+                    && half.rows > 0
+                {
+                    let position = (self.shifted_x(x), half.row(marker_row(half, hold)));
+                    self.settle(buf, position);
+                    buf[position].set_char('─').set_style(style);
+                }
+            }
+        }
+
+        if let Some(palette) = self.palette.take() {
+            palette.into_inner().flush(buf);
+        }
+
+        // Marker labels are drawn on top so they stay readable.
+        for (half, (min, max), markers) in markers {
+            for marker in markers.iter().filter(|_| half.rows > 0) {
+                let y = half.row(marker_row(half, self.curve(range_fraction(marker.value, min, max))));
+                let label_width = (marker.label.chars().count() as u16).min(inner_area.width);
+                buf.set_stringn(inner_area.right() - label_width, y, marker.label, width, marker.style);
+            }
+        }
+
+        let ranges = [(top.min, top.max), (bottom.min, bottom.max)];
+        self.render_axis_labels(area, inner_area, (top_half, bottom_half), ranges, buf);
+
+        if !self.stats.is_empty() {
+            for (series, prepared, half) in [(&self.top, &top, top_half), (&self.bottom, &bottom, bottom_half)] {
+                if let Some(text) = self.stats_text(series, prepared)
+                    && half.rows > 0
+                {
+                    let len = (text.chars().count() as u16).min(inner_area.width);
+                    let y = half.row(half.rows - 1);
+                    buf.set_stringn(inner_area.right() - len, y, text, width, Style::default().fg(Color::DarkGray));
+                }
+            }
+        }
+
+        if self.show_last_value {
+            let units = self.axis_units.unwrap_or(("", ""));
+            for (series, prepared, half, unit) in
+                [(&self.top, &top, top_half, units.0), (&self.bottom, &bottom, bottom_half, units.1)]
+            {
+                let Some(last) = prepared.last_sample.filter(|_| half.rows > 0) else {
+                    continue;
+                };
+                let text = self.value_text(series, prepared.indices[last], unit);
+                let len = (text.chars().count() as u16).min(inner_area.width);
+                let x = match self.direction {
+                    Direction::LeftToRight => inner_area.right() - len,
+                    Direction::RightToLeft => inner_area.left(),
+                };
+                let y = half.row(marker_row(half, prepared.values[last]));
+                buf.set_stringn(x, y, text, width, series.style);
+            }
+        }
+
+        if let Some(cursor) = self.cursor {
+            self.render_cursor(inner_area, (top_half, bottom_half), (&top, &bottom), capacity, cursor, buf);
+        }
+
+        if self.equalize {
+            let tag = self.label(Label::Equalized);
+            buf.set_stringn(inner_area.left(), inner_area.top(), tag, width, Style::default().fg(Color::DarkGray));
+        }
+
+        if self.offset > 0 {
+            self.render_scroll_indicator(inner_area, self.span(capacity), buf);
+        }
+
+        if let Some((width, height, highlight)) = self.overview {
+            self.render_overview(inner_area, self.span(capacity), (width, height), highlight, buf);
+        }
+
+        if let Some(mut observer) = self.on_render_column.take() {
+            for column in self.columns_of(inner_area, &top, &bottom) {
+                observer(&column);
+            }
+        }
+    }
+}
+
+impl<'a> WaveformWidget<'a> {
+    /// Raw value of sample `index` of `series`, formatted for a readout.
+    fn value_text(&self, series: &Series, index: usize, unit: &str) -> String {
+        self.value_label(series.data.values(index..index + 1)[0], unit)
+    }
+
+    /// `value` as shown in the axis labels and readouts: by the axis format
+    /// if set, else the localized number followed by `unit`.
+    fn value_label(&self, value: f64, unit: &str) -> String {
+        match &self.axis_format {
+            Some(format) => format(value),
+            None => format!("{}{unit}", self.label(Label::Value(value))),
+        }
+    }
+
+    /// Draws the crosshair at data index `cursor` and the value callouts.
+    fn render_cursor(
+        &self,
+        area: Rect,
+        (top_half, bottom_half): (Half, Half),
+        (top, bottom): (&Prepared, &Prepared),
+        capacity: usize,
+        cursor: usize,
+        buf: &mut Buffer,
+    ) {
+        let width = area.width as usize;
+        // The column whose sample is the closest to the cursor, if in view.
+        let columns: Vec<(u16, usize)> = (0..width)
+            .filter(|&relative_x| !(self.half_cell_shift() && relative_x == 0))
+            .filter_map(|relative_x| {
+                let slot = self.bar_slot(relative_x, width)?;
+                let index = [top, bottom]
+                    .into_iter()
+                    .find_map(|p| sample_index(self.alignment, p.indices.len(), capacity, slot).map(|i| p.indices[i]))?;
+                Some((self.shifted_x(self.column_x(area, relative_x)), index))
+            })
+            .collect();
+        let (first, last) = columns.iter().fold((usize::MAX, 0), |(lo, hi), &(_, i)| (lo.min(i), hi.max(i)));
+        if !(first..=last).contains(&cursor) {
+            return;
+        }
+        let Some(&(x, _)) = columns.iter().min_by_key(|&&(_, i)| i.abs_diff(cursor)) else {
+            return;
+        };
+
+        for y in area.top()..area.bottom() {
+            if buf[(x, y)].symbol() == " " {
+                buf[(x, y)].set_char('│').set_style(self.cursor_style);
+            }
+        }
+
+        let units = self.axis_units.unwrap_or(("", ""));
+        for (series, half, unit) in [(&self.top, top_half, units.0), (&self.bottom, bottom_half, units.1)] {
+            if half.rows == 0 || cursor >= series.data.len() {
+                continue;
+            }
+            let text = self.value_text(series, cursor, unit);
+            let len = text.chars().count() as u16;
+            // Beside the line, on the side with room for it.
+            let text_x = if x + 1 + len <= area.right() { x + 1 } else { x.saturating_sub(len).max(area.left()) };
+            buf.set_stringn(text_x, half.row(half.rows - 1), text, (area.right() - text_x) as usize, series.style);
+        }
+    }
+
+    /// Draws the corner ticks and the baseline arrowhead at the newest edge.
+    fn render_frame(&self, area: Rect, top_half: Half, bottom_half: Half, style: Style, buf: &mut Buffer) {
+        let corners = [
+            (area.left(), area.top(), '┌'),
+            (area.right() - 1, area.top(), '┐'),
+            (area.left(), area.bottom() - 1, '└'),
+            (area.right() - 1, area.bottom() - 1, '┘'),
+        ];
+        for (x, y, glyph) in corners {
+            buf[(x, y)].set_char(glyph).set_style(style);
+        }
+
+        if let Some(y) = self.axis_row(area, top_half, bottom_half) {
+            let x = self.column_x(area, area.width as usize - 1);
+            let arrowhead = match self.direction {
+                Direction::LeftToRight => '⡱',
+                Direction::RightToLeft => '⢎',
+            };
+            buf[(x, y)].set_char(arrowhead).set_style(style);
+        }
+    }
+
+    /// Draws the overview inset, highlighting the samples in view.
+    fn render_overview(&self, area: Rect, capacity: usize, (width, height): (u16, u16), highlight: Style, buf: &mut Buffer) {
+        let (width, height) = (width.min(area.width), height.min(area.height));
+        // Room for the border and at least one cell of chart.
+        if width < 3 || height < 3 {
+            return;
+        }
+        let x = match self.direction {
+            Direction::LeftToRight => area.left(),
+            Direction::RightToLeft => area.right() - width,
+        };
+        let inset = Rect::new(x, area.top(), width, height);
+
+        let overview = WaveformWidget::from_data(self.top.data.borrow(), self.bottom.data.borrow())
+            .mode(self.mode)
+            .top_style(self.top.style)
+            .bottom_style(self.bottom.style)
+            .top_range(self.top.min, self.top.max)
+            .bottom_range(self.bottom.min, self.bottom.max)
+            .baseline(self.baseline)
+            .direction(self.direction)
+            .color_depth(self.color_depth)
+            .color_space(self.color_space)
+            .downsample(Downsample::MinMax)
+            .fit(Fit::Stretch)
+            .block(Block::bordered().border_style(Style::default().fg(Color::DarkGray)));
+        let chart = overview.inner_area(inset);
+
+        // Columns of the overview showing the window, each standing for an
+        // equal share of the history.
+        let total = self.top.data.len().max(self.bottom.data.len());
+        let view = visible_range(self.total_len(), capacity, self.offset);
+        let start = self.window_range(total).start;
+        let window = start + view.start..start + view.end;
+        let (columns, total) = (chart.width as usize, total.max(1));
+        let highlighted = window.start * columns / total..(window.end * columns).div_ceil(total);
+        let xs: Vec<u16> = highlighted.map(|relative_x| overview.column_x(chart, relative_x)).collect();
+
+        Clear.render(inset, buf);
+        overview.render(inset, buf);
+        for x in xs {
+            for y in chart.top()..chart.bottom() {
+                buf[(x, y)].set_style(highlight);
+            }
+        }
+    }
+
+    /// Draws the position of the visible window within the longer series
+    /// along the bottom edge.
+    fn render_scroll_indicator(&self, area: Rect, capacity: usize, buf: &mut Buffer) {
+        let total = self.total_len();
+        if total <= capacity {
+            return;
+        }
+        let window = visible_range(total, capacity, self.offset);
+        let position = match self.direction {
+            Direction::LeftToRight => window.start,
+            Direction::RightToLeft => total - capacity - window.start,
+        };
+        let mut state = ScrollbarState::new(total - capacity + 1).position(position);
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::HorizontalBottom)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .track_symbol(None)
+            .thumb_symbol("━")
+            .thumb_style(Style::default().fg(Color::DarkGray));
+        ratatui::widgets::StatefulWidget::render(scrollbar, area, buf, &mut state);
+    }
+}
+
+/// Renders a horizontal chart into a transposed scratch buffer, then rotates it
+/// clockwise into `area`: time runs downwards, the top half extends to the right.
+///
+/// Text is not rotated: each run of text is drawn horizontally, on top of the
+/// rotated chart, starting where its first character lands (shifted left to fit).
+/// `frame` holds the halves prepared for the sideways chart.
+fn render_rotated(widget: WaveformWidget, area: Rect, frame: Frame, buf: &mut Buffer) {
+    let logical = frame.chart;
+    let mut scratch = Buffer::empty(logical);
+    widget.render_chart(logical, frame, &mut scratch);
+
+    let mut texts = Vec::new();
+    for ly in 0..logical.height {
+        let runs = text_runs(&scratch, ly);
+        for lx in 0..logical.width {
+            let cell = &scratch[(lx, ly)];
+            // Leave whatever was underneath cells the chart didn't touch.
+            if *cell == Cell::EMPTY || runs.iter().any(|(start, end)| (*start..*end).contains(&lx)) {
+                continue;
+            }
+            let glyph = cell.symbol().chars().next().map_or(' ', rotate_glyph);
+            let (x, y) = (area.right() - 1 - ly, area.top() + lx);
+            buf[(x, y)].set_char(glyph).set_style(cell.style());
+        }
+        for (start, end) in runs {
+            let text: String = (start..end).map(|lx| scratch[(lx, ly)].symbol()).collect();
+            let width = (end - start).min(area.width);
+            let x = (area.right() - 1 - ly).min(area.right() - width);
+            texts.push((x, area.top() + start, text, scratch[(start, ly)].style()));
+        }
+    }
+    for (x, y, text, style) in texts {
+        buf.set_stringn(x, y, &text, (area.right() - x) as usize, style);
+    }
+}
+
+/// Whether a glyph is text rather than part of the chart drawing.
+fn is_text(glyph: char) -> bool {
+    !matches!(glyph, ' ' | '\u{2500}'..='\u{259F}' | '\u{2800}'..='\u{28FF}')
+}
+
+/// Column ranges of the text runs in a row; single spaces between words and
+/// the trailing halves of wide characters belong to the run.
+fn text_runs(buf: &Buffer, y: u16) -> Vec<(u16, u16)> {
+    let area = buf.area;
+    let glyph = |x: u16| (x < area.right()).then(|| buf[(x, y)].symbol().chars().next());
+    let mut runs = Vec::new();
+    let mut x = area.left();
+    while x < area.right() {
+        if !glyph(x).flatten().is_some_and(is_text) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        loop {
+            x += 1;
+            match glyph(x) {
+                Some(None) => continue,
+                Some(Some(c)) if is_text(c) => continue,
+                Some(Some(' ')) if glyph(x + 1).flatten().is_some_and(is_text) => continue,
+                _ => break,
+            }
+        }
+        runs.push((start, x));
+    }
+    runs
+}
+
+/// Rotates a glyph a quarter turn clockwise (up becomes right, right becomes down).
+///
+/// Braille cells are 2 dots wide and 4 tall, so each pair of dot rows of the
+/// source collapses into one dot column: the upper pair lands on the right.
+fn rotate_glyph(glyph: char) -> char {
+    match glyph {
+        '▌' => '▀',
+        '▐' => '▄',
+        '▀' => '▐',
+        '▄' => '▌',
+        '▁' => '▏',
+        '▂' => '▎',
+        '▃' => '▍',
+        '▅' => '▋',
+        '▆' => '▊',
+        '▇' => '▉',
+        '▔' => '▕',
+        '│' => '─',
+        '─' => '│',
+        '━' => '┃',
+        '┊' => '┈',
+        '╌' => '╎',
+        '┌' => '┐',
+        '┐' => '┘',
+        '┘' => '└',
+        '└' => '┌',
+        '\u{2800}'..='\u{28FF}' => {
+            let bits = glyph as u32 - 0x2800;
+            // (source bits, target bit): source rows 0-1 -> right, 2-3 -> left;
+            // source left column -> top dot row, right column -> second dot row.
+            const MAP: [(u32, u32); 4] = [(0x03, 0x08), (0x44, 0x01), (0x18, 0x10), (0xA0, 0x02)];
+            let rotated = MAP.iter().filter(|(from, _)| bits & from != 0).fold(0, |acc, (_, to)| acc | to);
+            char::from_u32(0x2800 + rotated).unwrap_or(glyph)
+        }
+        _ => glyph,
+    }
+}
+
+/// Vertical extent of one half of the chart.
+#[derive(Debug, Clone, Copy)]
+struct Half {
+    /// Row adjacent to the baseline, where columns start growing from.
+    origin: u16,
+    /// Number of rows available to this half.
+    rows: u16,
+    /// Top halves grow upwards, bottom halves downwards.
+    is_top: bool,
+}
+
+impl Half {
+    /// Buffer row of the `i`-th cell counted away from the center.
+    fn row(&self, i: u16) -> u16 {
+        if self.is_top { self.origin - i } else { self.origin + i }
+    }
+}
+
+fn reference_markers<'a>(lines: &[(f64, Style, Option<&'a str>)]) -> Vec<Marker<'a>> {
+    lines.iter().map(|&(value, style, label)| Marker::new(value, label.unwrap_or("")).style(style)).collect()
+}
+
+fn sorted_zones(zones: &[Zone]) -> Vec<Zone> {
+    let mut zones = zones.to_vec();
+    zones.sort_by(|a, b| a.enter.total_cmp(&b.enter));
+    zones
+}
+
+/// Resolves the active zone of each value, walking the sequence in order so
+/// that a zone is only left once the value drops below its exit level.
+fn zone_levels(zones: &[Zone], values: &[f64]) -> Vec<Option<usize>> {
+    let mut level: Option<usize> = None;
+    values
+        .iter()
+        .map(|&v| {
+            // Climb into every zone whose entry level is reached...
+            while let Some(next) = zones.get(level.map_or(0, |l| l + 1)) {
+                if v < next.enter {
+                    break;
+                }
+                level = Some(level.map_or(0, |l| l + 1));
+            }
+            // ...then fall out of zones whose exit level is undercut.
+            while let Some(l) = level {
+                if v >= zones[l].exit {
+                    break;
+                }
+                level = l.checked_sub(1);
+            }
+            level
+        })
+        .collect()
+}
+
+/// Histogram equalization: maps a value to its rank among a set of samples.
+struct Equalizer {
+    sorted: Vec<f64>,
+}
+
+impl Equalizer {
+    fn new(values: impl Iterator<Item = f64>) -> Self {
+        let mut sorted: Vec<f64> = values.collect();
+        sorted.sort_by(f64::total_cmp);
+        Self { sorted }
+    }
+
+    /// Fraction of samples less than or equal to `val` (0.0 - 1.0).
+    fn apply(&self, val: f64) -> f64 {
+        if self.sorted.is_empty() {
+            return val;
+        }
+        let rank = self.sorted.partition_point(|&v| v <= val);
+        rank as f64 / self.sorted.len() as f64
+    }
+}
+
+/// Soft-knee compression of normalized values above a threshold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SoftClip {
+    threshold: f64,
+    knee: f64,
+    ratio: f64,
+}
+
+impl SoftClip {
+    /// Compressed value of `val`: unchanged below the knee, divided by the
+    /// ratio above the threshold, with a quadratic blend across the knee.
+    fn apply(&self, val: f64) -> f64 {
+        let Self { threshold, knee, ratio } = *self;
+        let over = val - threshold;
+        if 2.0 * over < -knee {
+            val
+        } else if 2.0 * over <= knee && knee > 0.0 {
+            val + (1.0 / ratio - 1.0) * (over + knee / 2.0).powi(2) / (2.0 * knee)
+        } else {
+            threshold + over / ratio
+        }
+    }
+}
+
+/// Largest value in `data`, floored to avoid dividing by zero when scaling.
+fn peak(data: &[f64]) -> f64 {
+    data.iter().fold(0.0f64, |a, &b| a.max(b)).max(f64::EPSILON)
+}
+
+/// Zoom and scroll offset the samples in view are taken at.
+#[derive(Debug, Clone, Copy)]
+struct Pan {
+    zoom: f64,
+    offset: usize,
+}
+
+impl Pan {
+    /// Number of samples in view for `capacity` columns at this zoom.
+    fn span(self, capacity: usize) -> usize {
+        ((capacity as f64 / self.zoom).round() as usize).max(1)
+    }
+}
+
+/// Layout and prepared halves of one render, shared by everything drawn in it.
+struct Frame {
+    /// Chart area within the block and the axis label gutters.
+    inner_area: Rect,
+    /// Area the columns are laid out in: `inner_area`, or its sideways
+    /// counterpart at the origin for vertical charts.
+    chart: Rect,
+    top: Prepared,
+    bottom: Prepared,
+}
+
+/// Visible samples of one half, normalized and styled, ready to be drawn.
+struct Prepared {
+    /// Normalized values (0.0 - 1.0).
+    values: Vec<f64>,
+    /// Normalized lowest value each sample stands for, the bucket minimum
+    /// when downsampling min/max (otherwise equal to `values`).
+    lows: Vec<f64>,
+    /// Index of the data sample behind each value.
+    indices: Vec<usize>,
+    /// Whether each sample is a gap (NaN) rather than a measurement.
+    gaps: Vec<bool>,
+    /// Position of the newest sample that is not a gap.
+    last_sample: Option<usize>,
+    /// Style and normalized cumulative top of each stacked layer, bottom-up.
+    stack: Vec<(Style, Vec<f64>)>,
+    /// Base style of each sample (series style with zone overrides).
+    styles: Vec<Style>,
+    /// Normalized underlay values (0.0 - 1.0), empty without an underlay.
+    underlay: Vec<f64>,
+    /// Whether each column connects to the previous sample (line modes).
+    connect: bool,
+    /// Whether columns span from their low to their value instead of from the center.
+    envelope: bool,
+    /// Value drawn at full height (the series max, or the autoscaled one).
+    max: f64,
+    /// Value at the baseline.
+    min: f64,
+    /// Normalized min-hold trace, empty without one.
+    min_hold: Vec<f64>,
+    /// Whether each sample exceeds the max of the half.
+    clipped: Vec<bool>,
+}
+
+impl Prepared {
+    /// Vertical extent and base style of the `i`-th visible sample.
+    fn sample(&self, i: usize) -> (Extent, Style) {
+        let val = self.values[i];
+        let extent = if self.connect {
+            let prev = i.checked_sub(1).map_or(val, |p| self.values[p]);
+            Extent { lo: prev.min(val).min(self.lows[i]), hi: prev.max(val) }
+        } else if self.envelope {
+            Extent { lo: self.lows[i].min(val), hi: val }
+        } else {
+            Extent { lo: 0.0, hi: val }
+        };
+        (extent, self.styles[i])
+    }
+}
+
+/// One visible sample before normalization.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Point {
+    value: f64,
+    /// Lowest value the sample stands for (the value itself unless downsampled).
+    low: f64,
+    /// Index of the data sample it comes from.
+    index: usize,
+}
+
+impl From<(f64, usize)> for Point {
+    fn from((value, index): (f64, usize)) -> Self {
+        Self { value, low: value, index }
+    }
+}
+
+/// Normalized vertical extent of a column (0.0 at the center, 1.0 at the edge).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Extent {
+    lo: f64,
+    hi: f64,
+}
+
+impl<'a> WaveformWidget<'a> {
+    /// Area left for the chart once the optional block is drawn.
+    fn inner_area(&self, area: Rect) -> Rect {
+        match self.axis_units {
+            Some(_) if self.orientation == Orientation::Horizontal => self.frame(area).inner_area,
+            _ => self.block_inner(area),
+        }
+    }
+
+    /// Lays the chart out in `area` and prepares both halves for it, once per
+    /// render. The axis label gutters are measured against the full width;
+    /// autoscaled maxima barely move once they are taken out, but the halves
+    /// are prepared again for the narrower chart.
+    fn frame(&self, area: Rect) -> Frame {
+        self.frame_at(area, self.pan())
+    }
+
+    /// [`frame`](Self::frame) at the given zoom and offset.
+    fn frame_at(&self, area: Rect, pan: Pan) -> Frame {
+        let inner = self.block_inner(area);
+        let layout = |inner_area: Rect| {
+            let chart = match self.orientation {
+                Orientation::Horizontal => inner_area,
+                Orientation::Vertical => Rect::new(0, 0, inner_area.height, inner_area.width),
+            };
+            let capacity = self.bar_capacity(chart.width as usize);
+            let top = self.prepare_at(&self.top, capacity, pan);
+            let bottom = self.prepare_at(&self.bottom, capacity, pan);
+            Frame { inner_area, chart, top, bottom }
+        };
+        let full = layout(inner);
+        let (left, right) = self.gutters(inner, [(full.top.min, full.top.max), (full.bottom.min, full.bottom.max)]);
+        if left + right == 0 || left + right >= inner.width {
+            return full;
+        }
+        layout(Rect::new(inner.x + left, inner.y, inner.width - left - right, inner.height))
+    }
+
+    /// Quantizes the samples in view into `inner`, keeping the level of the
+    /// last frame (`levels`) for samples that moved by less than one level,
+    /// and records the new levels.
+    fn hold_levels(&mut self, inner: Rect, levels: &mut [HashMap<usize, u16>; 2]) {
+        // Vertical charts are drawn sideways, then rotated.
+        let inner = match self.orientation {
+            Orientation::Horizontal => inner,
+            Orientation::Vertical => Rect::new(0, 0, inner.height, inner.width),
+        };
+        let capacity = self.bar_capacity(inner.width as usize);
+        let (top_half, bottom_half) = self.halves(inner);
+        let halves = [(&self.top, top_half), (&self.bottom, bottom_half)];
+        let mut held: [HashMap<usize, f64>; 2] = Default::default();
+        for ((series, half), (previous, held)) in halves.into_iter().zip(levels.iter_mut().zip(&mut held)) {
+            let steps = match self.mode {
+                WaveformMode::HighResBraille => half.rows * 4,
+                WaveformMode::BrailleLine => (half.rows * 4).saturating_sub(1),
+                WaveformMode::UltraThinBlock | WaveformMode::SolidBlock => half.rows,
+                WaveformMode::Density => 0,
+            };
+            if steps == 0 {
+                previous.clear();
+                continue;
+            }
+            let prepared = self.prepare(series, capacity);
+            let current: HashMap<usize, u16> = prepared
+                .values
+                .iter()
+                .zip(&prepared.indices)
+                .filter(|(value, _)| value.is_finite())
+                .map(|(value, &index)| {
+                    // Kept by sample number, which still matches once old samples are dropped.
+                    let number = series.first + index;
+                    let exact = value * steps as f64;
+                    let level = match previous.get(&number) {
+                        Some(&level) if (exact - level as f64).abs() < 1.0 => level,
+                        _ => exact.round() as u16,
+                    };
+                    (number, level)
+                })
+                .collect();
+            *held = current
+                .iter()
+                .map(|(&number, &level)| (number - series.first, level as f64 / steps as f64))
+                .collect();
+            *previous = current;
+        }
+        let [top, bottom] = held;
+        self.top.held = top;
+        self.bottom.held = bottom;
+    }
+
+    /// Value held by the dead band of `series` entering data index `start`:
+    /// the band runs from the anchor of the last frame when it lies up to
+    /// [`LOOK_BACK`] samples before `start` (from the first sample if the
+    /// anchor was dropped since), from `LOOK_BACK` samples back otherwise, so
+    /// scrolling and zooming don't move it.
+    fn dead_band_entry(&self, series: &Series, start: usize) -> Option<f64> {
+        let floor = start.saturating_sub(LOOK_BACK);
+        let anchor = series
+            .dead_band_anchor
+            .map(|(number, value)| (number.saturating_sub(series.first), value))
+            .filter(|&(index, _)| (floor..=start).contains(&index));
+        let (from, mut held) = anchor.map_or((floor, None), |(index, value)| (index, Some(value)));
+        for &value in series.data.values(from..start.min(series.data.len())).iter().filter(|v| v.is_finite()) {
+            match held {
+                Some(level) if (value - level).abs() < series.dead_band => {}
+                _ => held = Some(value),
+            }
+        }
+        held
+    }
+
+    /// Anchors the dead bands at the first sample in view, recording the held
+    /// values in `anchors` for the next frame, where old samples may be gone.
+    fn hold_dead_bands(&mut self, inner: Rect, anchors: &mut [Option<(usize, u64)>; 2]) {
+        let width = match self.orientation {
+            Orientation::Horizontal => inner.width,
+            Orientation::Vertical => inner.height,
+        };
+        let capacity = self.bar_capacity(width as usize);
+        for (series, anchor) in [&mut self.top, &mut self.bottom].into_iter().zip(anchors.iter_mut()) {
+            series.dead_band_anchor = anchor.map(|(number, bits)| (number, f64::from_bits(bits)));
+        }
+        let halves = [&self.top, &self.bottom];
+        for (series, anchor) in halves.into_iter().zip(anchors.iter_mut()) {
+            if series.dead_band <= 0.0 {
+                *anchor = None;
+                continue;
+            }
+            let start = self.prepare(series, capacity).indices.first().copied();
+            let entry = |start| Some((series.first + start, self.dead_band_entry(series, start)?.to_bits()));
+            *anchor = start.and_then(entry);
+        }
+    }
+
+    /// Stats overlay text of a half, `None` without samples in view.
+    fn stats_text(&self, series: &Series, prepared: &Prepared) -> Option<String> {
+        let (first, last) = (prepared.indices.first()?, prepared.indices.last()?);
+        let samples = series.data.values(*first..last + 1);
+        let samples: Vec<f64> = samples.iter().copied().filter(|v| v.is_finite()).collect();
+        if samples.is_empty() {
+            return None;
+        }
+        let stats: Vec<String> = self
+            .stats
+            .iter()
+            .map(|aggregate| {
+                let value = self.label(Label::Value((aggregate.fold)(&samples)));
+                format!("{} {value}", self.aggregate_name(aggregate))
+            })
+            .collect();
+        Some(stats.join(" "))
+    }
+
+    /// Name of `aggregate`, localized for the built-in ones.
+    fn aggregate_name(&self, aggregate: &Aggregate) -> String {
+        match aggregate.label {
+            Some(label) => self.label(label),
+            None => aggregate.name.to_string(),
+        }
+    }
+
+    /// Area inside the block, gutters included.
+    fn block_inner(&self, area: Rect) -> Rect {
+        match &self.block {
+            Some(b) => b.inner(area),
+            None => area,
+        }
+    }
+
+    /// Axis labels of the top and bottom halves as (row from the center, text),
+    /// for the `(min, max)` ranges of the halves.
+    fn axis_labels_for(&self, (top_half, bottom_half): (Half, Half), ranges: [(f64, f64); 2]) -> AxisLabels {
+        let Some((top_unit, bottom_unit)) = self.axis_units else {
+            return Default::default();
+        };
+        let labels = |half: Half, (min, max): (f64, f64), unit: &str| {
+            let text = |value: f64| self.value_label(value, unit);
+            let mut labels = match half.rows {
+                0 => return Vec::new(),
+                rows => vec![(0, text(min)), (rows - 1, text(max))],
+            };
+            // Round values in between, about one label every three rows, each
+            // with a free row around it so labels never touch.
+            let count = half.rows / 3;
+            if count > 0 && max > min {
+                let step = nice_step((max - min) / (count + 1) as f64);
+                let mut value = (min / step).floor() * step + step;
+                while value < max {
+                    let row = marker_row(half, self.curve(range_fraction(value, min, max)));
+                    if labels.iter().all(|(other, _)| row.abs_diff(*other) > 1) {
+                        labels.push((row, text(value)));
+                    }
+                    value += step;
+                }
+            }
+            labels
+        };
+        (labels(top_half, ranges[0], top_unit), labels(bottom_half, ranges[1], bottom_unit))
+    }
+
+    /// Widths of the left and right axis label gutters within `inner`, for
+    /// the `(min, max)` ranges of the halves.
+    fn gutters(&self, inner: Rect, ranges: [(f64, f64); 2]) -> (u16, u16) {
+        let Some((top_unit, bottom_unit)) = self.axis_units else {
+            return (0, 0);
+        };
+        if self.orientation == Orientation::Vertical {
+            return (0, 0);
+        }
+        let (top, bottom) = self.axis_labels_for(self.halves(inner), ranges);
+        // One blank column keeps the labels off the chart.
+        let width = |labels: &[(u16, String)]| {
+            labels.iter().map(|(_, text)| text.chars().count() as u16 + 1).max().unwrap_or(0)
+        };
+        if top_unit == bottom_unit { (width(&top).max(width(&bottom)), 0) } else { (width(&top), width(&bottom)) }
+    }
+
+    /// Draws the axis labels in the gutters beside `inner_area`.
+    fn render_axis_labels(
+        &self,
+        area: Rect,
+        inner_area: Rect,
+        halves: (Half, Half),
+        ranges: [(f64, f64); 2],
+        buf: &mut Buffer,
+    ) {
+        let Some((top_unit, bottom_unit)) = self.axis_units else {
+            return;
+        };
+        let inner = self.block_inner(area);
+        let (left, right) = (inner_area.left() - inner.left(), inner.right() - inner_area.right());
+        if inner_area.width == inner.width {
+            // Too narrow for the gutters.
+            return;
+        }
+        let (top, bottom) = self.axis_labels_for(halves, ranges);
+        let dual = top_unit != bottom_unit;
+        for (labels, half, style, on_right) in
+            [(top, halves.0, self.top.style, false), (bottom, halves.1, self.bottom.style, dual)]
+        {
+            for (row, text) in labels {
+                let y = half.row(row);
+                let len = text.chars().count() as u16;
+                if on_right {
+                    let width = right.saturating_sub(1);
+                    buf.set_stringn(inner_area.right() + 1, y, &text, width as usize, style);
+                } else {
+                    let width = left.saturating_sub(1);
+                    let x = inner_area.left() - left + width.saturating_sub(len);
+                    buf.set_stringn(x, y, &text, width as usize, style);
+                }
+            }
+        }
+    }
+
+    /// Splits the area into the top and bottom halves at the baseline.
+    /// The center gap rows are taken out of the height before splitting.
+    fn halves(&self, area: Rect) -> (Half, Half) {
+        let height = area.height.saturating_sub(self.center_gap);
+        let (top_rows, bottom_rows) = match self.baseline {
+            // The center split leaves the last row of odd heights empty.
+            Baseline::Center => (height / 2, height / 2),
+            Baseline::Bottom => (height, 0),
+            Baseline::Top => (0, height),
+            Baseline::Ratio(ratio) => {
+                let top_rows = (height as f64 * ratio.clamp(0.0, 1.0)).round() as u16;
+                (top_rows, height - top_rows)
+            }
+        };
+        let baseline_y = area.top() + top_rows;
+        (
+            Half { origin: baseline_y.saturating_sub(1), rows: top_rows, is_top: true },
+            Half { origin: baseline_y + self.center_gap, rows: bottom_rows, is_top: false },
+        )
+    }
+
+    /// Row of the axis line: the middle of the center gap, else the first row
+    /// of the bottom half (or the top half's when the bottom one is empty).
+    fn axis_row(&self, area: Rect, top_half: Half, bottom_half: Half) -> Option<u16> {
+        let y = if self.center_gap > 0 {
+            area.top() + top_half.rows + self.center_gap / 2
+        } else if bottom_half.rows > 0 {
+            bottom_half.origin
+        } else if top_half.rows > 0 {
+            top_half.origin
+        } else {
+            return None;
+        };
+        (y < area.bottom()).then_some(y)
+    }
+
+    /// Number of bars that fit in `width` columns.
+    fn bar_capacity(&self, width: usize) -> usize {
+        (width + self.bar_gap) / (self.bar_width + self.bar_gap)
+    }
+
+    /// Maps a column to its bar slot, counted from the right edge (0 = newest),
+    /// or `None` for gap columns and leftover columns that can't hold a full bar.
+    fn bar_slot(&self, relative_x: usize, width: usize) -> Option<usize> {
+        let pitch = self.bar_width + self.bar_gap;
+        let from_right = width - 1 - relative_x;
+        let slot = from_right / pitch;
+        (from_right % pitch < self.bar_width && slot < self.bar_capacity(width)).then_some(slot)
+    }
+
+    /// Range of the data of `len` samples the widget renders.
+    fn window_range(&self, len: usize) -> Range<usize> {
+        match &self.window {
+            Some(window) => window.start.min(len)..window.end.min(len),
+            None => 0..len,
+        }
+    }
+
+    /// Number of samples in the window of the longer series.
+    fn total_len(&self) -> usize {
+        [&self.top, &self.bottom].iter().map(|series| self.window_range(series.data.len()).len()).max().unwrap_or(0)
+    }
+
+    /// Sample number just past the newest sample in the window of either series.
+    fn end_sample(&self) -> usize {
+        [&self.top, &self.bottom]
+            .iter()
+            .map(|series| series.first + self.window_range(series.data.len()).end)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Normalizes the visible part of a series and resolves its per-sample styles.
+    fn prepare(&self, series: &Series, capacity: usize) -> Prepared {
+        self.prepare_at(series, capacity, self.pan())
+    }
+
+    /// [`prepare`](Self::prepare) at the given zoom and offset.
+    fn prepare_at(&self, series: &Series, capacity: usize, pan: Pan) -> Prepared {
+        let window = self.window_range(series.data.len());
+        let data = series.data.slice(window.clone());
+
+        // Raw visible values, each with the data index it stands for.
+        let end = data.len().saturating_sub(pan.offset);
+        let mut visible: Vec<Point> = match (series.timestamps, self.downsample) {
+            (Some(times), _) => {
+                let times = &times[window.start.min(times.len())..window.end.min(times.len())];
+                // Samples without a timestamp are dropped before the offset is taken off.
+                let end = data.len().min(times.len()).saturating_sub(pan.offset);
+                time_weighted(&data.values(0..end), &times[..end], capacity).into_iter().map(Point::from).collect()
+            }
+            _ if pan.zoom != 1.0 => self.zoomed(&data, capacity, pan),
+            (None, Some(method)) if end > capacity => downsample(&data.slice(0..end), capacity, method),
+            _ => self.visible(&data, capacity, pan.offset).into_iter().map(Point::from).collect(),
+        };
+        visible.iter_mut().for_each(|p| p.index += window.start);
+        if let Some(lows) = &series.lows {
+            visible.iter_mut().for_each(|p| p.low = p.low.min(lows.get(p.index).copied().unwrap_or(p.low)));
+        }
+        let mut last_valid = None;
+        for p in &mut visible {
+            if p.value.is_finite() {
+                last_valid = Some(p.value);
+                continue;
+            }
+            if series.data.missing(p.index) {
+                (p.value, p.low) = (f64::NAN, f64::NAN);
+                continue;
+            }
+            let value = match self.invalid {
+                Invalid::Skip => f64::NAN,
+                Invalid::Zero => 0.0,
+                Invalid::HoldLast => last_valid.unwrap_or(f64::NAN),
+            };
+            (p.value, p.low) = (value, value);
+        }
+        if series.dead_band > 0.0 {
+            // Gaps are kept, without resetting the held value.
+            let mut held = visible.first().and_then(|p| self.dead_band_entry(series, p.index));
+            for p in visible.iter_mut().filter(|p| !p.value.is_nan()) {
+                match held {
+                    Some(value) if (p.value - value).abs() < series.dead_band => (p.value, p.low) = (value, value),
+                    _ => held = Some(p.value),
+                }
+            }
+        }
+        let raw: Vec<f64> = visible.iter().map(|p| p.value).collect();
+        let gaps: Vec<bool> = raw.iter().map(|v| v.is_nan()).collect();
+        let last_sample = gaps.iter().rposition(|&gap| !gap);
+
+        // Raw values of the stacked layers at each visible sample.
+        let layers: Vec<Vec<f64>> = series
+            .layers
+            .iter()
+            .map(|(data, _)| visible.iter().map(|p| data.get(p.index).map_or(0.0, |v| v.max(0.0))).collect())
+            .collect();
+
+        // Normalize data based on the min - max range (default 0.0 - 1.0), or
+        // on the column total for 100% stacks.
+        let percent = self.stacking == Stacking::Percent && !layers.is_empty();
+        let min = if percent { 0.0 } else { series.min };
+        let max = match self.autoscale {
+            Some((headroom, percentile)) => {
+                let totals: Vec<f64> =
+                    (0..raw.len()).map(|i| raw[i].max(0.0) + layers.iter().map(|layer| layer[i]).sum::<f64>()).collect();
+                let peak = percentile_of(&totals, percentile).max(self.decayed_peak(series, &visible));
+                min + (peak - min).max(f64::EPSILON) * (1.0 + headroom)
+            }
+            None => series.max,
+        };
+        let scales: Vec<f64> = (0..raw.len())
+            .map(|i| {
+                if percent {
+                    let total = raw[i].max(0.0) + layers.iter().map(|layer| layer[i]).sum::<f64>();
+                    if total > 0.0 { total } else { 1.0 }
+                } else {
+                    max
+                }
+            })
+            .collect();
+        let normalize = |v: f64, i: usize| self.curve(range_fraction(v, min, scales[i])).clamp(0.0, 1.0);
+        let mut values: Vec<f64> = raw.iter().enumerate().map(|(i, &v)| normalize(v, i)).collect();
+        let mut lows: Vec<f64> = visible.iter().enumerate().map(|(i, p)| normalize(p.low, i)).collect();
+
+        // Cumulative tops of the layers above the data.
+        let mut cumulative: Vec<f64> = raw.iter().map(|v| v.max(0.0)).collect();
+        let stack = layers
+            .iter()
+            .zip(&series.layers)
+            .map(|(layer, &(_, style))| {
+                let tops = cumulative
+                    .iter_mut()
+                    .zip(layer)
+                    .enumerate()
+                    .map(|(i, (sum, v))| {
+                        *sum += v;
+                        normalize(*sum, i)
+                    })
+                    .collect();
+                (style, tops)
+            })
+            .collect();
+        let clipped = cumulative.iter().zip(&scales).map(|(total, scale)| total > scale).collect();
+
+        // Zones are evaluated on the raw values, before any display transform.
+        // Value colors go beneath zones, metadata styles are patched on top of
+        // zones, style callbacks last.
+        let styles = zone_levels(&series.zones, &raw)
+            .into_iter()
+            .zip(&visible)
+            .zip(&values)
+            .map(|((level, &Point { index, value, .. }), &height)| {
+                let style = match color::color_at(&self.value_stops, height, self.color_depth, self.color_space) {
+                    Some(color) => series.style.fg(color),
+                    None => series.style,
+                };
+                let style = level.map_or(style, |z| style.patch(series.zones[z].style));
+                let style = match series.metadata.as_ref().and_then(|metadata| metadata(index)) {
+                    Some(meta_style) => style.patch(meta_style),
+                    None => style,
+                };
+                match &series.style_fn {
+                    Some(style_fn) => style.patch(style_fn(index, value)),
+                    None => style,
+                }
+            })
+            .collect();
+
+        if self.equalize {
+            let eq = Equalizer::new(values.iter().copied());
+            values.iter_mut().for_each(|v| *v = eq.apply(*v));
+            lows.iter_mut().for_each(|v| *v = eq.apply(*v));
+        }
+
+        // Heights held from the last frame by the hysteresis.
+        for (value, p) in values.iter_mut().zip(&visible) {
+            if let Some(&held) = series.held.get(&p.index) {
+                *value = held;
+            }
+        }
+
+        let underlay = series.underlay.map_or_else(Vec::new, |underlay| {
+            let range = self.window_range(underlay.len());
+            let underlay = &underlay[range];
+            let underlay = Samples::Floats(Cow::Borrowed(underlay));
+            self.visible(&underlay, capacity, pan.offset)
+                .iter()
+                .map(|(v, _)| self.curve(range_fraction(*v, min, max)).clamp(0.0, 1.0))
+                .collect()
+        });
+
+        let connect = self.mode == WaveformMode::BrailleLine;
+        let indices = visible.iter().map(|p| p.index).collect();
+        let envelope = series.lows.is_some();
+        // The floor drops to every new low and creeps back up between them.
+        let min_hold = series.min_hold.map_or_else(Vec::new, |(rise, _)| {
+            let mut hold = f64::INFINITY;
+            (0..values.len())
+                .map(|i| {
+                    hold = if gaps[i] { hold + rise } else { lows[i].min(hold + rise) };
+                    hold
+                })
+                .collect()
+        });
+
+        Prepared {
+            values,
+            lows,
+            indices,
+            styles,
+            gaps,
+            last_sample,
+            stack,
+            underlay,
+            connect,
+            envelope,
+            max,
+            min,
+            min_hold,
+            clipped,
+        }
+    }
+
+    /// Maps a normalized value through the amplitude scale and the soft clip, if any.
+    fn curve(&self, val: f64) -> f64 {
+        let val = self.scale.apply(val);
+        self.soft_clip.map_or(val, |clip| clip.apply(val))
+    }
+
+    /// Peak of the samples before the oldest visible one, each shrunk by the
+    /// autoscale decay per sample of distance, so a spike fades out of the
+    /// scale once it scrolls off instead of vanishing.
+    fn decayed_peak(&self, series: &Series, visible: &[Point]) -> f64 {
+        let Some(oldest) = visible.iter().map(|p| p.index).min() else {
+            return 0.0;
+        };
+        if self.autoscale_decay <= 0.0 {
+            return 0.0;
+        }
+        // Samples further back than this count for less than 0.1%.
+        let reach = if self.autoscale_decay < 1.0 {
+            (0.001f64.ln() / self.autoscale_decay.ln()).ceil() as usize
+        } else {
+            LOOK_BACK
+        };
+        let reach = reach.min(LOOK_BACK).min(oldest);
+        let older = series.data.values(oldest - reach..oldest);
+        older
+            .iter()
+            .rev()
+            .zip(1..)
+            .filter(|(v, _)| v.is_finite())
+            .map(|(v, distance)| v * self.autoscale_decay.powi(distance))
+            .fold(0.0, f64::max)
+    }
+
+    /// Zoom and scroll offset of the widget.
+    fn pan(&self) -> Pan {
+        Pan { zoom: self.zoom, offset: self.offset }
+    }
+
+    /// Number of samples in view for `capacity` columns at the current zoom.
+    fn span(&self, capacity: usize) -> usize {
+        self.pan().span(capacity)
+    }
+
+    /// Samples in view at the `pan` zoom: buckets of samples when zoomed
+    /// out, samples repeated over several columns when zoomed in.
+    fn zoomed(&self, data: &Samples, capacity: usize, pan: Pan) -> Vec<Point> {
+        let window = visible_range(data.len(), pan.span(capacity), pan.offset);
+        let data = data.slice(window.clone());
+        let mut points = if data.len() > capacity {
+            downsample(&data, capacity, self.downsample.unwrap_or(Downsample::MinMax))
+        } else {
+            let values = data.values(0..data.len());
+            let columns = ((values.len() as f64 * pan.zoom).round() as usize).clamp(values.len(), capacity);
+            (0..columns)
+                .map(|i| {
+                    let j = i * values.len() / columns;
+                    Point::from((values[j], j))
+                })
+                .collect()
+        };
+        points.iter_mut().for_each(|p| p.index += window.start);
+        points
+    }
+
+    /// Samples in view `offset` samples back, stretched to `capacity` if the
+    /// fit asks for it, paired with the index of the (nearest) data sample
+    /// they come from.
+    fn visible(&self, data: &Samples, capacity: usize, offset: usize) -> Vec<(f64, usize)> {
+        let window = visible_range(data.len(), capacity, offset);
+        let visible = data.values(window.clone());
+        let linear = match self.fit {
+            Fit::Stretch => true,
+            Fit::StretchNearest => false,
+            Fit::Natural => return visible.iter().copied().zip(window).collect(),
+        };
+        if visible.is_empty() || visible.len() >= capacity {
+            return visible.iter().copied().zip(window).collect();
+        }
+
+        let scale = (visible.len() - 1) as f64 / (capacity - 1).max(1) as f64;
+        (0..capacity)
+            .map(|i| {
+                let pos = i as f64 * scale;
+                let nearest = pos.round() as usize;
+                let value = if linear {
+                    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+                    visible[lo] + (visible[hi] - visible[lo]) * pos.fract()
+                } else {
+                    visible[nearest]
+                };
+                (value, window.start + nearest)
+            })
+            .collect()
+    }
+
+    /// Brightness of a column for the horizontal fade (1.0 when disabled).
+    fn fade_factor(&self, relative_x: usize, width: usize) -> f64 {
+        if !self.fade_effect {
+            return 1.0;
+        }
+        // 0.0 (oldest) to 1.0 (newest)
+        let linear = relative_x as f64 / width as f64;
+        // Delayed fade: columns past the fade start keep full brightness,
+        // older ones dim along the curve.
+        let boundary = 1.0 - self.fade_start;
+        if boundary <= 0.0 || linear > boundary { 1.0 } else { self.fade_curve.apply(linear / boundary) }
+    }
+
+    /// Draws one column of a half, from the center outwards.
+    /// With `merge`, Braille dots already in a cell are kept (stacked layers).
+    fn render_column(&self, buf: &mut Buffer, x: u16, half: Half, sample: (Extent, Style), fade_factor: f64, merge: bool) {
+        let (extent, base_style) = sample;
+        let mut glyphs = self.column_glyphs(extent, half);
+
+        // Half a cell of scroll phase moves the column into the right half of the
+        // previous cell. Mirrored, columns sit in the right half of their cell
+        // and move into the left half of the next one.
+        // The newest column can be flushed against the outer edge instead.
+        let outer = self.direction == Direction::LeftToRight;
+        let right = if self.edge_x == Some(x) { outer } else { self.half_cell_shift() == outer };
+        if right {
+            glyphs.iter_mut().flatten().for_each(|g| *g = right_column(*g));
+        }
+        let x = self.shifted_x(x);
+
+        // The last glyph is the tip of the column (the waveform contour).
+        let tip = glyphs.len().saturating_sub(1);
+        for (i, glyph) in glyphs.into_iter().enumerate() {
+            // Cells below the extent (line modes) are left untouched.
+            let Some(glyph) = glyph else {
+                continue;
+            };
+            let position = (x, half.row(i as u16));
+            let cell = &mut buf[position];
+            let glyph = match cell.symbol().chars().next() {
+                Some(below) if merge => merge_braille(below, glyph),
+                _ => glyph,
+            };
+            cell.set_char(glyph);
+            let style = || self.cell_style(base_style, i as u16, half.rows, i == tip, fade_factor);
+            match &self.palette {
+                Some(palette) => {
+                    let key = (base_style, i as u16, half.rows, i == tip, fade_factor.to_bits());
+                    palette.borrow_mut().set(buf, position, key, style);
+                }
+                None => {
+                    buf[position].set_style(style());
+                }
+            }
+        }
+    }
+
+    /// Writes the palette style still pending at `position`, so whatever is
+    /// drawn there next goes on top of it.
+    fn settle(&self, buf: &mut Buffer, position: (u16, u16)) {
+        if let Some(palette) = &self.palette {
+            palette.borrow_mut().settle(buf, position);
+        }
+    }
+
+    /// Draws the stacked layers of the `i`-th sample above its base column.
+    fn render_stack(&self, buf: &mut Buffer, x: u16, half: Half, prepared: &Prepared, i: usize, fade_factor: f64) {
+        let mut lo = prepared.values[i];
+        for (style, tops) in &prepared.stack {
+            let extent = Extent { lo, hi: tops[i].max(lo) };
+            if extent.hi > extent.lo {
+                self.render_column(buf, x, half, (extent, *style), fade_factor, true);
+            }
+            lo = extent.hi;
+        }
+    }
+
+    /// Draws one underlay column as dithered Braille, from the center outwards.
+    fn render_underlay(&self, buf: &mut Buffer, x: u16, half: Half, val: f64, base_style: Style, fade_factor: f64) {
+        let dots = (val * half.rows as f64 * 4.0).round() as u16;
+        let x = self.shifted_x(x);
+
+        for (i, glyph) in braille_glyphs(0, dots, half).into_iter().enumerate() {
+            let Some(glyph) = glyph else {
+                continue;
+            };
+            let style = self.cell_style(base_style, i as u16, half.rows, false, fade_factor);
+            self.settle(buf, (x, half.row(i as u16)));
+            buf[(x, half.row(i as u16))].set_char(dither(glyph, x)).set_style(style);
+        }
+    }
+
+    /// Paints the cells a column covers, from the baseline up to its extent.
+    fn render_under_curve(&self, buf: &mut Buffer, x: u16, half: Half, extent: Extent, style: Style) {
+        let x = self.shifted_x(x);
+        let rows = self.column_glyphs(extent, half).len() as u16;
+        for row in 0..rows {
+            self.settle(buf, (x, half.row(row)));
+            buf[(x, half.row(row))].set_style(style);
+        }
+    }
+
+    /// Buffer column of the `relative_x`-th column counted from the oldest edge.
+    fn column_x(&self, area: Rect, relative_x: usize) -> u16 {
+        match self.direction {
+            Direction::LeftToRight => area.left() + relative_x as u16,
+            Direction::RightToLeft => area.right() - 1 - relative_x as u16,
+        }
+    }
+
+    /// Buffer column a column at `x` is drawn in once the scroll phase is applied.
+    fn shifted_x(&self, x: u16) -> u16 {
+        match (self.half_cell_shift(), self.direction) {
+            (false, _) => x,
+            (true, Direction::LeftToRight) => x - 1,
+            (true, Direction::RightToLeft) => x + 1,
+        }
+    }
+
+    /// Whether the scroll phase moves columns into the right half of the previous cell.
+    /// Area the chart is drawn in, shifted by the burn-in protection.
+    fn burn_in_area(&self, area: Rect) -> Rect {
+        const STEP_SECS: u64 = 300;
+        if !self.burn_in || area.width < 2 || area.height < 2 {
+            return area;
+        }
+        let step = self.frame_time.as_secs() / STEP_SECS % 4;
+        let (dx, dy) = [(0, 0), (1, 0), (1, 1), (0, 1)][step as usize];
+        Rect::new(area.x + dx, area.y + dy, area.width - 1, area.height - 1)
+    }
+
+    fn half_cell_shift(&self) -> bool {
+        self.scroll_phase >= 0.5 && !matches!(self.mode, WaveformMode::Density | WaveformMode::SolidBlock)
+    }
+
+    /// Glyphs of a column in the current mode, from the center outwards.
+    /// `None` marks cells inside the column that stay empty.
+    fn column_glyphs(&self, extent: Extent, half: Half) -> Vec<Option<char>> {
+        match self.mode {
+            WaveformMode::HighResBraille => {
+                let dots = |v: f64| (v * half.rows as f64 * 4.0).round() as u16;
+                braille_glyphs(dots(extent.lo), dots(extent.hi), half)
+            }
+            WaveformMode::BrailleLine => {
+                // One dot per sample: levels go from the first to the last dot row.
+                let top_dot = (half.rows * 4).saturating_sub(1) as f64;
+                let level = |v: f64| (v * top_dot).round() as u16;
+                if half.rows == 0 {
+                    return Vec::new();
+                }
+                braille_glyphs(level(extent.lo), level(extent.hi) + 1, half)
+            }
+            WaveformMode::UltraThinBlock => block_glyphs(extent, half, '▌'),
+            WaveformMode::SolidBlock if self.eighths => eighth_glyphs(extent, half),
+            WaveformMode::SolidBlock => block_glyphs(extent, half, '█'),
+            WaveformMode::Density => density_glyphs(extent.hi, half),
+        }
+    }
+
+    /// Computes the final style of a single cell.
+    fn cell_style(&self, base_style: Style, row: u16, rows: u16, is_tip: bool, fade_factor: f64) -> Style {
+        let mut style = if !self.gradient_stops.is_empty() && self.value_stops.is_empty() {
+            // Each row takes the color at its middle.
+            let position = (row as f64 + 0.5) / rows as f64;
+            let color = color::color_at(&self.gradient_stops, position, self.color_depth, self.color_space);
+            base_style.fg(color.unwrap_or(Color::Reset))
+        } else if self.gradient_effect {
+            // Calculate height ratio (0.0 at center, 1.0 at peak)
+            let height_ratio = row as f64 / rows as f64;
+            let height_ratio = match self.gradient_direction {
+                GradientDirection::BrightCenter => height_ratio,
+                GradientDirection::BrightPeaks => 1.0 - (row + 1) as f64 / rows as f64,
+            };
+            color::gradient(base_style, height_ratio, self.gradient_strength, self.color_depth, self.color_space)
+        } else {
+            base_style
+        };
+
+        if is_tip && let Some(edge_style) = self.edge_style {
+            style = style.patch(edge_style);
+        }
+
+        // Apply fade LAST so it dims whatever color we have
+        let mut style = color::fade(style, fade_factor, self.color_depth, self.color_space);
+        if is_tip && let Some(peak_style) = self.peak_style {
+            style = style.patch(peak_style);
+        }
+        color::quantize(style, self.color_depth)
+    }
+}
+
+/// Inputs of [`WaveformWidget::cell_style`]: base style, row, rows, tip, fade bits.
+type StyleKey = (Style, u16, u16, bool, u64);
+
+/// Distinct cell styles of one frame, and the palette index still to be
+/// written for each cell of the chart area.
+struct Palette {
+    area: Rect,
+    styles: Vec<Style>,
+    lookup: HashMap<StyleKey, usize>,
+    pending: Vec<Option<usize>>,
+}
+
+impl Palette {
+    fn new(area: Rect) -> Self {
+        Self { area, styles: Vec::new(), lookup: HashMap::new(), pending: vec![None; area.area() as usize] }
+    }
+
+    fn cell(&self, (x, y): (u16, u16)) -> Option<usize> {
+        self.area
+            .contains((x, y).into())
+            .then(|| (y - self.area.y) as usize * self.area.width as usize + (x - self.area.x) as usize)
+    }
+
+    /// Records the style of `key` (computed by `style` on first use) as pending
+    /// at `position`, settling the style pending there before.
+    fn set(&mut self, buf: &mut Buffer, position: (u16, u16), key: StyleKey, style: impl FnOnce() -> Style) {
+        let index = *self.lookup.entry(key).or_insert_with(|| {
+            self.styles.push(style());
+            self.styles.len() - 1
+        });
+        match self.cell(position) {
+            Some(cell) => {
+                self.settle(buf, position);
+                self.pending[cell] = Some(index);
+            }
+            None => {
+                buf[position].set_style(self.styles[index]);
+            }
+        }
+    }
+
+    /// Writes the style pending at `position`, if any.
+    fn settle(&mut self, buf: &mut Buffer, position: (u16, u16)) {
+        if let Some(index) = self.cell(position).and_then(|cell| self.pending[cell].take()) {
+            buf[position].set_style(self.styles[index]);
+        }
+    }
+
+    /// Writes every pending style in a single pass, one `set_style` per run
+    /// of cells sharing a style within a row.
+    fn flush(self, buf: &mut Buffer) {
+        let width = self.area.width as usize;
+        for (row, pending) in self.pending.chunks(width.max(1)).enumerate() {
+            let y = self.area.y + row as u16;
+            let mut start = 0;
+            while start < pending.len() {
+                let len = pending[start..].iter().take_while(|&&index| index == pending[start]).count();
+                if let Some(index) = pending[start] {
+                    let run = Rect::new(self.area.x + start as u16, y, len as u16, 1);
+                    buf.set_style(run, self.styles[index]);
+                }
+                start += len;
+            }
+        }
+    }
+}
+
+/// Reduces `data` to `buckets` points. Expects more samples than buckets.
+fn downsample(data: &Samples, buckets: usize, method: Downsample) -> Vec<Point> {
+    let mean = |values: &mut dyn Iterator<Item = f64>, len: usize| values.sum::<f64>() / len as f64;
+    match method {
+        Downsample::MinMax => reduce_buckets(data, buckets, |bucket, range| {
+            let (i, value) = bucket.iter().copied().enumerate().fold((0, bucket[0]), |best, (i, v)| {
+                if v > best.1 { (i, v) } else { best }
+            });
+            let low = bucket.iter().copied().fold(value, f64::min);
+            Point { value, low, index: range.start + i }
+        }),
+        Downsample::Average => reduce_buckets(data, buckets, |bucket, range| {
+            Point::from((mean(&mut bucket.iter().copied(), bucket.len()), range.end - 1))
+        }),
+        Downsample::Rms => reduce_buckets(data, buckets, |bucket, range| {
+            Point::from((mean(&mut bucket.iter().map(|v| v * v), bucket.len()).sqrt(), range.end - 1))
+        }),
+        Downsample::Lttb => lttb(data, buckets),
+    }
+}
+
+/// Splits `data` into `buckets` equally sized runs of samples and reduces
+/// each run, given with its index range, to one point.
+fn reduce_buckets(data: &Samples, buckets: usize, reduce: impl Fn(&[f64], Range<usize>) -> Point) -> Vec<Point> {
+    let n = data.len();
+    (0..buckets)
+        .map(|b| {
+            let range = b * n / buckets..(b + 1) * n / buckets;
+            // Read one bucket at a time, so large sources are never materialized.
+            reduce(&data.values(range.clone()), range)
+        })
+        .collect()
+}
+
+/// Largest-Triangle-Three-Buckets selection of `threshold` of the samples of
+/// `data`, keeping the first and last sample.
+fn lttb(data: &Samples, threshold: usize) -> Vec<Point> {
+    let n = data.len();
+    let value = |i: usize| data.values(i..i + 1)[0];
+    let point = |i: usize| Point::from((value(i), i));
+    if threshold >= n || threshold < 3 {
+        return (n.saturating_sub(threshold)..n).map(point).collect();
+    }
+
+    let every = (n - 2) as f64 / (threshold - 2) as f64;
+    let bucket_start = |b: usize| ((b as f64 * every) as usize + 1).min(n - 1);
+    let mut selected = vec![point(0)];
+    let (mut a, mut ay) = (0, value(0));
+    for b in 0..threshold - 2 {
+        // Average of the next bucket (the last sample for the last bucket).
+        let next = bucket_start(b + 1)..bucket_start(b + 2).max(bucket_start(b + 1) + 1).min(n);
+        let avg_x = next.clone().sum::<usize>() as f64 / next.len() as f64;
+        let avg_y = data.values(next.clone()).iter().sum::<f64>() / next.len() as f64;
+
+        // Sample of this bucket forming the largest triangle with `a` and the average.
+        let ax = a as f64;
+        let range = bucket_start(b)..bucket_start(b + 1);
+        let bucket = data.values(range.clone());
+        let area = |j: usize| ((ax - avg_x) * (bucket[j] - ay) - (ax - (range.start + j) as f64) * (avg_y - ay)).abs();
+        let best = (0..bucket.len()).fold(0, |best, j| if area(j) > area(best) { j } else { best });
+        (a, ay) = (range.start + best, bucket[best]);
+        selected.push(Point::from((ay, a)));
+    }
+    selected.push(point(n - 1));
+    selected
+}
+
+/// Splits the time range of `values` into `buckets` equal-length buckets and
+/// returns the time-weighted mean of each, treating the values as a step
+/// function. Each bucket is paired with the index of its longest-lasting sample.
+///
+/// The newest sample holds for the average sample interval, where the range
+/// ends. Non-finite values don't count; buckets without any finite value are NaN.
+fn time_weighted(values: &[f64], times: &[f64], buckets: usize) -> Vec<(f64, usize)> {
+    let (Some(&first), Some(&last)) = (times.first(), times.last()) else {
+        return Vec::new();
+    };
+    if last <= first || buckets == 0 {
+        return vec![(values[values.len() - 1], values.len() - 1)];
+    }
+
+    let end = last + (last - first) / (times.len() - 1) as f64;
+    // Segment `i` holds values[i] from times[i] until the next timestamp.
+    let until = |i: usize| times.get(i + 1).copied().unwrap_or(end);
+    let step = (end - first) / buckets as f64;
+    let mut segment = 0;
+    (0..buckets)
+        .map(|b| {
+            let (lo, hi) = (first + b as f64 * step, first + (b + 1) as f64 * step);
+            let (mut sum, mut weight, mut longest) = (0.0, 0.0, (0.0, segment));
+            let mut i = segment;
+            while i < times.len() && times[i] < hi {
+                let overlap = until(i).min(hi) - times[i].max(lo);
+                if overlap > 0.0 && values[i].is_finite() {
+                    sum += values[i] * overlap;
+                    weight += overlap;
+                    if overlap > longest.0 {
+                        longest = (overlap, i);
+                    }
+                }
+                if until(i) <= hi {
+                    segment = i + 1;
+                }
+                i += 1;
+            }
+            (if weight > 0.0 { sum / weight } else { f64::NAN }, longest.1)
+        })
+        .collect()
+}
+
+/// Range of the samples in view: the `capacity` samples ending `offset`
+/// samples before the newest one.
+fn visible_range(len: usize, capacity: usize, offset: usize) -> Range<usize> {
+    let end = len.saturating_sub(offset);
+    end.saturating_sub(capacity)..end
+}
+
+/// Index of the sample drawn in bar `slot` (0 = rightmost) when `len` samples
+/// are placed in `capacity` slots, or `None` for slots left blank.
+fn sample_index(alignment: Alignment, len: usize, capacity: usize, slot: usize) -> Option<usize> {
+    // Free slots to the right of the newest sample.
+    let free = capacity.saturating_sub(len);
+    let pad = match alignment {
+        Alignment::Left => free,
+        // Odd leftovers go to the right, like centered text.
+        Alignment::Center => free - free / 2,
+        Alignment::Right => 0,
+    };
+    (len + pad).checked_sub(slot + 1).filter(|&i| i < len)
+}
+
+/// Smallest 1, 2 or 5 times a power of ten that is at least `step`.
+fn nice_step(step: f64) -> f64 {
+    let magnitude = 10f64.powf(step.log10().floor());
+    [1.0, 2.0, 5.0, 10.0].into_iter().map(|m| m * magnitude).find(|&nice| nice >= step * (1.0 - 1e-9)).unwrap_or(step)
+}
+
+/// Position of `value` within `min..=max` (0.0 at `min`, 1.0 at `max`).
+fn range_fraction(value: f64, min: f64, max: f64) -> f64 {
+    (value - min) / (max - min).max(f64::EPSILON)
+}
+
+/// Compact text of a value: whole numbers without decimals, others
+/// with up to two.
+fn format_value(value: f64) -> String {
+    if (value - value.round()).abs() < 1e-9 {
+        return format!("{value:.0}");
+    }
+    let text = format!("{value:.2}");
+    text.trim_end_matches('0').to_string()
+}
+
+/// Nearest-rank `percentile` (0 - 100) of the finite values in `data`, 0.0
+/// when there are none.
+fn percentile_of(data: &[f64], percentile: f64) -> f64 {
+    let mut sorted: Vec<f64> = data.iter().copied().filter(|v| v.is_finite()).collect();
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    sorted.sort_by(f64::total_cmp);
+    let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Row (counted from the center) holding a normalized value within a half.
+fn marker_row(half: Half, val: f64) -> u16 {
+    let row = (val.clamp(0.0, 1.0) * half.rows as f64).floor() as u16;
+    row.min(half.rows.saturating_sub(1))
+}
+
+/// Braille glyphs of a column covering the dots `from..to` (counted from the
+/// center, 4 dots per cell), from the center outwards.
+fn braille_glyphs(from: u16, to: u16, half: Half) -> Vec<Option<char>> {
+    let fill = if half.is_top { get_thin_braille_fill } else { get_thin_braille_fill_bottom };
+    // Dot pattern of the first `dots` dots of a cell.
+    let bits = |dots: u16| match dots {
+        0 => 0,
+        d => fill(d.min(4) as u8) as u32 - 0x2800,
+    };
+
+    (0..to.div_ceil(4))
+        .map(|i| {
+            let cell_from = from.saturating_sub(i * 4);
+            let cell_to = to - i * 4;
+            let pattern = bits(cell_to) & !bits(cell_from);
+            (pattern != 0).then(|| char::from_u32(0x2800 + pattern).unwrap_or(' '))
+        })
+        .collect()
+}
+
+/// Block glyphs of a column, from the center outwards (1 step per cell).
+fn block_glyphs(extent: Extent, half: Half, glyph: char) -> Vec<Option<char>> {
+    let rows = |v: f64| ((v * half.rows as f64).round() as usize).min(half.rows as usize);
+    // Cells below the extent stay empty.
+    (0..rows(extent.hi)).map(|i| (i >= rows(extent.lo)).then_some(glyph)).collect()
+}
+
+/// Tip glyphs filling 1 to 7 eighths of a cell from the center, for halves
+/// growing up and down. Only drawn unrotated for the vertical orientation,
+/// where they turn into left and right partial blocks; the right side has no
+/// eighths, so the downward table rounds to the nearest available fill.
+const RISING_EIGHTHS: [char; 7] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇'];
+const FALLING_EIGHTHS: [char; 7] = ['▔', '▔', '▀', '▀', '▀', '▀', '█'];
+
+/// Solid block glyphs of a column with the tip cell filled in eighths.
+fn eighth_glyphs(extent: Extent, half: Half) -> Vec<Option<char>> {
+    let eighths = |v: f64| ((v * half.rows as f64 * 8.0).round() as usize).min(half.rows as usize * 8);
+    let lo = ((extent.lo * half.rows as f64).round() as usize).min(half.rows as usize);
+    let hi = eighths(extent.hi);
+    let tips = if half.is_top { RISING_EIGHTHS } else { FALLING_EIGHTHS };
+    let mut glyphs: Vec<_> = (0..hi / 8).map(|i| (i >= lo).then_some('█')).collect();
+    if hi % 8 > 0 && hi / 8 >= lo {
+        glyphs.push(Some(tips[hi % 8 - 1]));
+    }
+    glyphs
+}
+
+/// Moves a left-column glyph to the right column of its cell.
+fn right_column(glyph: char) -> char {
+    match glyph as u32 {
+        // Braille: dots 1, 2, 3, 7 -> dots 4, 5, 6, 8
+        bits @ 0x2800..=0x28FF => {
+            let bits = bits - 0x2800;
+            let right = ((bits & 0x07) << 3) | ((bits & 0x40) << 1);
+            char::from_u32(0x2800 + right).unwrap_or(glyph)
+        }
+        _ if glyph == '▌' => '▐',
+        _ => glyph,
+    }
+}
+
+/// Spreads the dots of a left-column Braille glyph over a checkerboard: each
+/// dot row keeps its dot in the left or right column, alternating by row and
+/// by cell column `x`.
+fn dither(glyph: char, x: u16) -> char {
+    // (left, right) dot bits of each dot row, top to bottom.
+    const ROWS: [(u32, u32); 4] = [(0x01, 0x08), (0x02, 0x10), (0x04, 0x20), (0x40, 0x80)];
+    let bits = glyph as u32 - 0x2800;
+    let dithered = ROWS
+        .iter()
+        .enumerate()
+        .filter(|(_, (left, _))| bits & left != 0)
+        .fold(0, |acc, (row, (left, right))| acc | if (row + x as usize).is_multiple_of(2) { left } else { right });
+    char::from_u32(0x2800 + dithered).unwrap_or(glyph)
+}
+
+/// Combines the dots of two Braille glyphs; other glyphs are replaced.
+fn merge_braille(below: char, glyph: char) -> char {
+    match (below as u32, glyph as u32) {
+        (a @ 0x2800..=0x28FF, b @ 0x2800..=0x28FF) => char::from_u32(a | b).unwrap_or(glyph),
+        _ => glyph,
+    }
+}
+
+/// Number of dot rows a column glyph covers (full cells count 4).
+fn glyph_dots(glyph: char) -> u16 {
+    match glyph as u32 {
+        bits @ 0x2800..=0x28FF => {
+            let bits = bits - 0x2800;
+            [(0x01, 0x08), (0x02, 0x10), (0x04, 0x20), (0x40, 0x80)]
+                .iter()
+                .filter(|(left, right)| bits & (left | right) != 0)
+                .count() as u16
+        }
+        _ => 4,
+    }
+}
+
+/// Density glyphs of a column: every row of the half, shaded by the value.
+fn density_glyphs(val: f64, half: Half) -> Vec<Option<char>> {
+    let shade = match val {
+        v if v <= 0.0 => return Vec::new(),
+        v if v <= 0.25 => '░',
+        v if v <= 0.5 => '▒',
+        v if v <= 0.75 => '▓',
+        _ => '█',
+    };
+    vec![Some(shade); half.rows as usize]
+}
+
+// This function must only be called when mode is HighResBraille.
+// height_in_dots must be between 1 and 4 inclusive.
+fn get_thin_braille_fill(height_in_dots: u8) -> char {
+    match height_in_dots {
+        // Dot 7 only
+        1 => '\u{2840}', // ⡀
+        // Dots 7 and 3
+        2 => '\u{2844}', // ⡄
+        // Dots 7, 3, and 2
+        3 => '\u{2846}', // ⡆
+        // Dots 7, 3, 2, and 1 (Full left column)
+        4 => '\u{2847}', // ⡇
+        // Fallback for safety
+        _ => ' ',
+    }
+}
+
+// This function must only be called when mode is HighResBraille.
+// height_in_dots must be between 1 and 4 inclusive.
+// Returns characters with dots aligned to the TOP of the cell (for growing downwards).
+fn get_thin_braille_fill_bottom(height_in_dots: u8) -> char {
+    match height_in_dots {
+        // Dot 1 only (Top Left)
+        1 => '\u{2801}', // ⠁
+        // Dots 1 and 2 (Top two)
         2 => '\u{2803}', // ⠃
         // Dots 1, 2, and 3 (Top three)
         3 => '\u{2807}', // ⠇
@@ -493,3 +4312,1814 @@ fn get_thin_braille_fill_bottom(height_in_dots: u8) -> char {
         _ => ' ',
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_thin_braille_fill() {
+        assert_eq!(get_thin_braille_fill(1), '\u{2840}');
+        assert_eq!(get_thin_braille_fill(2), '\u{2844}');
+        assert_eq!(get_thin_braille_fill(3), '\u{2846}');
+        assert_eq!(get_thin_braille_fill(4), '\u{2847}');
+        assert_eq!(get_thin_braille_fill(0), ' ');
+        assert_eq!(get_thin_braille_fill(5), ' ');
+    }
+
+    #[test]
+    fn test_get_thin_braille_fill_bottom() {
+        assert_eq!(get_thin_braille_fill_bottom(1), '\u{2801}');
+        assert_eq!(get_thin_braille_fill_bottom(2), '\u{2803}');
+        assert_eq!(get_thin_braille_fill_bottom(3), '\u{2807}');
+        assert_eq!(get_thin_braille_fill_bottom(4), '\u{2847}');
+        assert_eq!(get_thin_braille_fill_bottom(0), ' ');
+        assert_eq!(get_thin_braille_fill_bottom(5), ' ');
+    }
+
+    #[test]
+    fn test_presets() {
+        let rx = [10.0, 40.0, 20.0];
+        let tx = [5.0, 2.0];
+
+        let net = WaveformWidget::network(&rx, &tx);
+        assert_eq!(net.top.max, 40.0);
+        assert_eq!(net.bottom.max, 5.0);
+        assert!(net.fade_effect);
+
+        let owned = WaveformWidget::owned(vec![0.5], Vec::new());
+        assert_eq!(owned.top.data.values(0..1), [0.5].as_slice());
+
+        let delta = WaveformWidget::delta(&[0.0, 5.0, 1.0], &[3.0, 2.0]);
+        assert_eq!(delta.top.data.values(0..2), [2.0, 0.0].as_slice());
+        assert_eq!(delta.bottom.data.values(0..2), [0.0, 1.0].as_slice());
+        assert_eq!((delta.top.max, delta.bottom.max), (2.0, 2.0));
+
+        let audio = WaveformWidget::audio(&[0.5, 1.0]);
+        assert_eq!(audio.top.data.values(0..2), audio.bottom.data.values(0..2));
+        assert!(audio.gradient_effect);
+
+        let metric = WaveformWidget::metric(&[0.5]);
+        assert_eq!(metric.bottom.data.len(), 0);
+        assert_eq!(metric.mode, WaveformMode::UltraThinBlock);
+
+        // Presets are only starting points.
+        let net = WaveformWidget::network(&rx, &tx).fade_effect(false).top_max(100.0);
+        assert!(!net.fade_effect);
+        assert_eq!(net.top.max, 100.0);
+    }
+
+    #[test]
+    fn test_render_uneven_halves() {
+        let area = Rect::new(0, 0, 3, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::metric(&[1.0, 1.0]).render(area, &mut buf);
+
+        // Right-aligned top half, nothing below the center.
+        assert_eq!(buf[(0, 0)].symbol(), " ");
+        assert_eq!(buf[(1, 0)].symbol(), "▌");
+        assert_eq!(buf[(2, 0)].symbol(), "▌");
+        assert_eq!(buf[(2, 1)].symbol(), " ");
+    }
+
+    #[test]
+    fn test_render_separators() {
+        let area = Rect::new(0, 0, 7, 2);
+        let data = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        let rows = |widget: WaveformWidget| {
+            let mut buf = Buffer::empty(area);
+            widget.mode(WaveformMode::UltraThinBlock).separators(3).render(area, &mut buf);
+            (0..2).map(|y| (0..7).map(|x| buf[(x, y)].symbol()).collect::<String>()).collect::<Vec<_>>()
+        };
+
+        // Before samples 3 and 6; data is drawn over the separator.
+        assert_eq!(rows(WaveformWidget::new(&data, &[])), ["   ▌  │", "   │  │"]);
+        // They move with the samples when scrolled, zoomed or numbered.
+        assert_eq!(rows(WaveformWidget::new(&data, &[]).offset(2)), ["     ▌ ", "     │ "]);
+        assert_eq!(rows(WaveformWidget::new(&data, &[]).zoom(2.0)), ["▌▌    │", "      │"]);
+        assert_eq!(rows(WaveformWidget::new(&data, &[]).first_sample(1, 0)), ["  │▌ │ ", "  │  │ "]);
+    }
+
+    #[test]
+    fn test_render_edge_style() {
+        let area = Rect::new(0, 0, 1, 6);
+        let mut buf = Buffer::empty(area);
+        let body = Color::Rgb(0, 100, 0);
+        let edge = Color::Rgb(200, 255, 200);
+        WaveformWidget::new(&[0.5], &[1.0])
+            .top_style(Style::default().fg(body))
+            .bottom_style(Style::default().fg(body))
+            .edge_style(Style::default().fg(edge))
+            .render(area, &mut buf);
+
+        // Top: 6 of 12 dots -> two cells above the center, tip at row 1.
+        assert_eq!(buf[(0, 2)].fg, body);
+        assert_eq!(buf[(0, 1)].fg, edge);
+        assert_eq!(buf[(0, 1)].symbol(), "⡄");
+        // Bottom: full height, tip at the last row.
+        assert_eq!(buf[(0, 4)].fg, body);
+        assert_eq!(buf[(0, 5)].fg, edge);
+    }
+
+    #[test]
+    fn test_render_peak_style() {
+        let area = Rect::new(0, 0, 4, 4);
+        let body = Color::Rgb(0, 100, 0);
+        let tip = Color::Rgb(255, 255, 255);
+        let render = |widget: WaveformWidget| {
+            let mut buf = Buffer::empty(area);
+            widget.mode(WaveformMode::SolidBlock).render(area, &mut buf);
+            buf
+        };
+        let widget = || {
+            WaveformWidget::new(&[1.0; 4], &[])
+                .top_style(Style::default().fg(body))
+                .gradient_effect(true)
+                .fade_effect(true)
+        };
+
+        // Peaks keep their style over the gradient and the fade...
+        let peaks = render(widget().peak_style(Style::default().fg(tip)));
+        assert!((0..4).all(|x| peaks[(x, 0)].fg == tip));
+        assert_ne!(peaks[(0, 1)].fg, body);
+        // ...while the edge style fades out towards the oldest column.
+        let edges = render(widget().edge_style(Style::default().fg(tip)));
+        assert_eq!(edges[(3, 0)].fg, tip);
+        assert_ne!(edges[(0, 0)].fg, tip);
+    }
+
+    #[test]
+    fn test_render_density() {
+        let area = Rect::new(0, 0, 5, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[0.0, 0.2, 0.4, 0.6, 1.0], &[1.0])
+            .mode(WaveformMode::Density)
+            .render(area, &mut buf);
+
+        let top: String = (0..5).map(|x| buf[(x, 0)].symbol()).collect();
+        assert_eq!(top, " ░▒▓█");
+        assert_eq!(buf[(4, 1)].symbol(), "█");
+    }
+
+    #[test]
+    fn test_to_text() {
+        let text = WaveformWidget::new(&[0.0, 1.0, 1.0], &[1.0])
+            .mode(WaveformMode::UltraThinBlock)
+            .top_style(Style::default().fg(Color::Rgb(0, 255, 0)))
+            .to_text(4, 2);
+
+        assert_eq!(text.lines.len(), 2);
+        assert_eq!(text.lines[0].width(), 4);
+        assert_eq!(text.lines[1].width(), 4);
+
+        // Leading blanks merge into one span, the two filled cells into another.
+        let top = &text.lines[0].spans;
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].content, "  ");
+        assert_eq!(top[1].content, "▌▌");
+        assert_eq!(top[1].style.fg, Some(Color::Rgb(0, 255, 0)));
+    }
+
+    #[test]
+    fn test_equalizer() {
+        // A nearly flat signal spreads over the full range.
+        let eq = Equalizer::new([0.50, 0.51, 0.52, 0.53].into_iter());
+        assert_eq!(eq.apply(0.50), 0.25);
+        assert_eq!(eq.apply(0.52), 0.75);
+        assert_eq!(eq.apply(0.53), 1.0);
+        assert_eq!(eq.apply(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_render_equalized() {
+        let area = Rect::new(0, 0, 4, 8);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[0.50, 0.51, 0.52, 0.53], &[])
+            .mode(WaveformMode::UltraThinBlock)
+            .equalize(true)
+            .render(area, &mut buf);
+
+        // The newest (largest) sample now reaches the top of its half.
+        assert_eq!(buf[(3, 0)].symbol(), "▌");
+        assert_eq!(buf[(0, 3)].symbol(), "▌");
+        assert_eq!(buf[(0, 2)].symbol(), " ");
+        assert_eq!(buf[(1, 0)].symbol(), "Q");
+    }
+
+    #[test]
+    fn test_zone_levels_hysteresis() {
+        let red = Style::default().fg(Color::Red);
+        let zones = [Zone::hysteresis(0.9, 0.8, red)];
+
+        // Enters above 0.9 and stays until the value drops below 0.8.
+        let levels = zone_levels(&zones, &[0.85, 0.95, 0.85, 0.81, 0.79, 0.85]);
+        assert_eq!(levels, vec![None, Some(0), Some(0), Some(0), None, None]);
+
+        // Plain thresholds switch immediately.
+        let zones = [Zone::new(0.5, red), Zone::new(0.8, red)];
+        let levels = zone_levels(&zones, &[0.1, 0.9, 0.6, 0.4]);
+        assert_eq!(levels, vec![None, Some(1), Some(0), None]);
+    }
+
+    #[test]
+    fn test_render_zones() {
+        let area = Rect::new(0, 0, 3, 2);
+        let mut buf = Buffer::empty(area);
+        let red = Color::Rgb(255, 0, 0);
+        WaveformWidget::new(&[0.95, 0.85, 0.7], &[])
+            .top_style(Style::default().fg(Color::Rgb(0, 255, 0)))
+            .top_zones(&[Zone::hysteresis(0.9, 0.8, Style::default().fg(red))])
+            .render(area, &mut buf);
+
+        assert_eq!(buf[(0, 0)].fg, red);
+        assert_eq!(buf[(1, 0)].fg, red);
+        assert_eq!(buf[(2, 0)].fg, Color::Rgb(0, 255, 0));
+    }
+
+    #[test]
+    fn test_render_zones_raw_units() {
+        let area = Rect::new(0, 0, 3, 2);
+        let mut buf = Buffer::empty(area);
+        let red = Color::Rgb(255, 0, 0);
+        // Levels are in data units: 400 of 500, not 0.8 of the scaled value.
+        WaveformWidget::new(&[450.0, 420.0, 390.0], &[])
+            .mode(WaveformMode::SolidBlock)
+            .top_max(500.0)
+            .top_style(Style::default().fg(Color::Rgb(0, 255, 0)))
+            .top_zones(&[Zone::new(400.0, Style::default().fg(red))])
+            .render(area, &mut buf);
+
+        assert_eq!(buf[(0, 0)].fg, red);
+        assert_eq!(buf[(1, 0)].fg, red);
+        assert_eq!(buf[(2, 0)].fg, Color::Rgb(0, 255, 0));
+    }
+
+    #[test]
+    fn test_render_reflection() {
+        let area = Rect::new(0, 0, 2, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[1.0, 0.5], &[0.0, 0.0])
+            .mode(WaveformMode::UltraThinBlock)
+            .top_style(Style::default().fg(Color::Rgb(200, 100, 0)))
+            .gradient_effect(true)
+            .reflection_effect(true)
+            .render(area, &mut buf);
+
+        // The top column is mirrored below the center at 30% brightness.
+        assert_eq!(buf[(0, 1)].fg, Color::Rgb(200, 100, 0));
+        assert_eq!(buf[(0, 2)].fg, Color::Rgb(60, 30, 0));
+        assert_eq!(buf[(0, 3)].symbol(), "▌");
+        assert_eq!(buf[(1, 2)].symbol(), "▌");
+        assert_eq!(buf[(1, 3)].symbol(), " ");
+
+        // Rows further out are darker in the reflection too.
+        let (Color::Rgb(outer, ..), Color::Rgb(inner, ..)) = (buf[(0, 3)].fg, buf[(0, 0)].fg) else {
+            panic!("expected rgb colors");
+        };
+        assert!(outer < inner);
+    }
+
+    #[test]
+    fn test_render_bars() {
+        let area = Rect::new(0, 0, 8, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[1.0, 0.0, 1.0], &[])
+            .mode(WaveformMode::UltraThinBlock)
+            .bar_width(2)
+            .bar_gap(1)
+            .render(area, &mut buf);
+
+        // Bars are 2 cells wide with 1 blank cell in between, newest on the right.
+        let top: String = (0..8).map(|x| buf[(x, 0)].symbol()).collect();
+        assert_eq!(top, "▌▌    ▌▌");
+    }
+
+    #[test]
+    fn test_bar_slots() {
+        let widget = WaveformWidget::new(&[], &[]).bar_width(2).bar_gap(1);
+        assert_eq!(widget.bar_capacity(8), 3);
+        let slots: Vec<_> = (0..8).map(|x| widget.bar_slot(x, 8)).collect();
+        assert_eq!(slots, [Some(2), Some(2), None, Some(1), Some(1), None, Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn test_newest_styles() {
+        let green = Color::Rgb(0, 255, 0);
+        let red = Color::Rgb(255, 0, 0);
+        let widget = WaveformWidget::new(&[0.2, 0.95], &[])
+            .top_style(Style::default().fg(green))
+            .top_zones(&[Zone::new(0.9, Style::default().fg(red))]);
+
+        let (top, bottom) = widget.newest_styles(Rect::new(0, 0, 10, 4));
+        assert_eq!(top.and_then(|s| s.fg), Some(red));
+        assert_eq!(bottom, None);
+    }
+
+    #[test]
+    fn test_newest_styles_intensity() {
+        let area = Rect::new(0, 0, 4, 4);
+        let widget = || {
+            WaveformWidget::new(&[], &[1.0; 4])
+                .mode(WaveformMode::SolidBlock)
+                .bottom_style(Style::default().fg(Color::Rgb(200, 100, 0)))
+                .bottom_intensity(0.5)
+        };
+        let mut buf = Buffer::empty(area);
+        widget().render(area, &mut buf);
+
+        // The newest bottom cell next to the center line.
+        assert_eq!(widget().newest_styles(area).1.and_then(|s| s.fg), buf[(3, 2)].style().fg);
+        assert_eq!(buf[(3, 2)].fg, Color::Rgb(100, 50, 0));
+    }
+
+    #[test]
+    fn test_render_markers() {
+        let area = Rect::new(0, 0, 6, 8);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[10.0], &[])
+            .mode(WaveformMode::UltraThinBlock)
+            .top_max(10.0)
+            .top_markers(&[Marker::new(6.0, "cm")])
+            .render(area, &mut buf);
+
+        // 60% of the 4 top rows -> third row above the center (y = 1).
+        let row: String = (0..6).map(|x| buf[(x, 1)].symbol()).collect();
+        assert_eq!(row, "╌╌╌╌cm");
+        // The data is drawn over the line, the label over the data.
+        assert_eq!(buf[(5, 3)].symbol(), "▌");
+        assert_eq!(buf[(5, 1)].symbol(), "m");
+        assert_eq!(buf[(0, 5)].symbol(), " ");
+    }
+
+    #[test]
+    fn test_right_column() {
+        assert_eq!(right_column('\u{2847}'), '\u{28B8}'); // ⡇ -> ⢸
+        assert_eq!(right_column('\u{2840}'), '\u{2880}'); // ⡀ -> ⢀
+        assert_eq!(right_column('\u{2801}'), '\u{2808}'); // ⠁ -> ⠈
+        assert_eq!(right_column('▌'), '▐');
+        assert_eq!(right_column('█'), '█');
+    }
+
+    #[test]
+    fn test_render_scroll_phase() {
+        let area = Rect::new(0, 0, 3, 2);
+        let render = |phase: f64| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::new(&[1.0, 1.0, 1.0], &[])
+                .mode(WaveformMode::UltraThinBlock)
+                .scroll_phase(phase)
+                .render(area, &mut buf);
+            (0..3).map(|x| buf[(x, 0)].symbol().to_string()).collect::<String>()
+        };
+
+        assert_eq!(render(0.0), "▌▌▌");
+        assert_eq!(render(0.3), "▌▌▌");
+        // Half a cell later the oldest sample scrolled out and the rest moved left.
+        assert_eq!(render(0.5), "▐▐ ");
+    }
+
+    #[test]
+    fn test_braille_glyphs_span() {
+        let top = Half { origin: 1, rows: 2, is_top: true };
+        // Filled column of 6 dots: full cell, then two dots.
+        assert_eq!(braille_glyphs(0, 6, top), vec![Some('\u{2847}'), Some('\u{2844}')]);
+        // Dots 5..7 only: nothing in the first cell, the middle of the second.
+        assert_eq!(braille_glyphs(5, 7, top), vec![None, Some('\u{2806}')]);
+
+        let bottom = Half { origin: 0, rows: 2, is_top: false };
+        assert_eq!(braille_glyphs(3, 5, bottom), vec![Some('\u{2840}'), Some('\u{2801}')]);
+    }
+
+    #[test]
+    fn test_render_braille_line() {
+        let area = Rect::new(0, 0, 3, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[0.0, 1.0, 1.0], &[])
+            .mode(WaveformMode::BrailleLine)
+            .render(area, &mut buf);
+
+        // A flat line at zero sits on the lowest dot.
+        assert_eq!(buf[(0, 1)].symbol(), "⡀");
+        assert_eq!(buf[(0, 0)].symbol(), " ");
+        // The jump to 1.0 is joined by a vertical run through both rows.
+        assert_eq!(buf[(1, 1)].symbol(), "⡇");
+        assert_eq!(buf[(1, 0)].symbol(), "⡇");
+        // A flat line at the top is a single dot in the top row.
+        assert_eq!(buf[(2, 0)].symbol(), "⠁");
+        assert_eq!(buf[(2, 1)].symbol(), " ");
+    }
+
+    #[test]
+    fn test_render_metadata() {
+        let area = Rect::new(0, 0, 3, 2);
+        let mut buf = Buffer::empty(area);
+        let green = Color::Rgb(0, 255, 0);
+        let red = Color::Rgb(255, 0, 0);
+        let levels = ["info", "error", "info"];
+        WaveformWidget::new(&[1.0, 1.0, 1.0], &[])
+            .top_style(Style::default().fg(green))
+            .top_metadata(&levels, |level| (*level == "error").then(|| Style::default().fg(red)))
+            .render(area, &mut buf);
+
+        assert_eq!(buf[(0, 0)].fg, green);
+        assert_eq!(buf[(1, 0)].fg, red);
+        assert_eq!(buf[(2, 0)].fg, green);
+    }
+
+    #[test]
+    fn test_render_ansi16_fade() {
+        let area = Rect::new(0, 0, 8, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[1.0; 8], &[])
+            .top_style(Style::default().fg(Color::LightGreen))
+            .fade_effect(true)
+            .color_depth(ColorDepth::Ansi16)
+            .render(area, &mut buf);
+
+        // Named colors survive the fade, stepping down through the hue.
+        assert_eq!(buf[(7, 0)].fg, Color::LightGreen);
+        assert_eq!(buf[(4, 0)].fg, Color::LightGreen);
+        assert_eq!((buf[(3, 0)].fg, buf[(3, 0)].modifier), (Color::Green, ratatui::style::Modifier::BOLD));
+        assert_eq!((buf[(2, 0)].fg, buf[(2, 0)].modifier), (Color::Green, ratatui::style::Modifier::empty()));
+        assert_eq!(buf[(1, 0)].fg, Color::DarkGray);
+        assert_eq!(buf[(0, 0)].fg, Color::Black);
+    }
+
+    #[test]
+    fn test_slo_preset() {
+        let green = Color::Rgb(0, 255, 0);
+        let red = Color::Rgb(255, 0, 0);
+        let bands = [
+            SloBand::new(0.0, "fast", Style::default().fg(green)),
+            SloBand::new(300.0, "slow", Style::default().fg(red)),
+        ];
+        let widget = WaveformWidget::slo(&[100.0, 400.0], &bands);
+        // The peak (400) exceeds the 25% headroom above the last boundary (375).
+        assert_eq!(widget.top.max, 400.0);
+        assert_eq!(widget.top.markers.len(), 1);
+
+        let (newest, _) = widget.newest_styles(Rect::new(0, 0, 10, 8));
+        assert_eq!(newest.and_then(|s| s.fg), Some(red));
+    }
+
+    #[test]
+    fn test_rotate_glyph() {
+        assert_eq!(rotate_glyph('\u{2847}'), '\u{2809}'); // ⡇ -> ⠉
+        assert_eq!(rotate_glyph('\u{2840}'), '\u{2801}'); // ⡀ -> ⠁ (bottom dot lands on the left)
+        assert_eq!(rotate_glyph('\u{2801}'), '\u{2808}'); // ⠁ -> ⠈ (top dot lands on the right)
+        assert_eq!(rotate_glyph('▌'), '▀');
+        assert_eq!(rotate_glyph('█'), '█');
+    }
+
+    #[test]
+    fn test_render_vertical() {
+        let area = Rect::new(0, 0, 4, 3);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[0.5, 1.0], &[1.0, 0.0])
+            .mode(WaveformMode::UltraThinBlock)
+            .orientation(Orientation::Vertical)
+            .render(area, &mut buf);
+
+        let rows: Vec<String> = (0..3).map(|y| (0..4).map(|x| buf[(x, y)].symbol()).collect()).collect();
+        // Oldest sample at the top, newest at the bottom; top series to the right.
+        assert_eq!(rows, ["    ", "▀▀▀ ", "  ▀▀"]);
+    }
+
+    #[test]
+    fn test_render_vertical_eighths() {
+        let area = Rect::new(0, 0, 4, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[0.3, 0.75], &[0.3, 0.75])
+            .mode(WaveformMode::SolidBlock)
+            .orientation(Orientation::Vertical)
+            .render(area, &mut buf);
+
+        let rows: Vec<String> = (0..2).map(|y| (0..4).map(|x| buf[(x, y)].symbol()).collect()).collect();
+        // 0.3 of two cells is 5 eighths; 0.75 is one cell and a half.
+        assert_eq!(rows, [" ▐▋ ", "▐██▌"]);
+    }
+
+    #[test]
+    fn test_render_vertical_text() {
+        let area = Rect::new(0, 0, 8, 6);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[0.5; 6], &[])
+            .mode(WaveformMode::SolidBlock)
+            .orientation(Orientation::Vertical)
+            .top_markers(&[Marker::new(0.5, "mid")])
+            .render(area, &mut buf);
+
+        let rows: Vec<String> = (0..6).map(|y| (0..8).map(|x| buf[(x, y)].symbol()).collect()).collect();
+        // The marker label reads left to right, shifted in from the edge, over the marker line.
+        assert_eq!(rows, ["    ██╎ ", "    ██╎ ", "    ██╎ ", "    █mid", "    ██  ", "    ██  "]);
+    }
+
+    #[test]
+    fn test_render_solid_block() {
+        let area = Rect::new(0, 0, 2, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[1.0, 0.5], &[0.5, 0.0])
+            .mode(WaveformMode::SolidBlock)
+            .render(area, &mut buf);
+
+        let rows: Vec<String> = (0..4).map(|y| (0..2).map(|x| buf[(x, y)].symbol()).collect()).collect();
+        assert_eq!(rows, ["█ ", "██", "█ ", "  "]);
+    }
+
+    #[test]
+    fn test_render_underlay() {
+        let area = Rect::new(0, 0, 3, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[0.0, 0.5], &[])
+            .top_underlay(&[1.0, 1.0, 1.0])
+            .render(area, &mut buf);
+
+        // Checkerboard alternating by column; the data covers the underlay.
+        assert_eq!(buf[(0, 0)].symbol(), "⢕");
+        assert_eq!(buf[(1, 0)].symbol(), "⡪");
+        assert_eq!(buf[(2, 0)].symbol(), "⡄");
+        assert_eq!(dither('⠃', 1), '⠊');
+    }
+
+    #[test]
+    fn test_render_baseline() {
+        let area = Rect::new(0, 0, 1, 4);
+        let render = |baseline| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::new(&[1.0], &[])
+                .mode(WaveformMode::SolidBlock)
+                .baseline(baseline)
+                .render(area, &mut buf);
+            (0..4).map(|y| buf[(0, y)].symbol()).collect::<String>()
+        };
+
+        assert_eq!(render(Baseline::Center), "██  ");
+        assert_eq!(render(Baseline::Bottom), "████");
+        assert_eq!(render(Baseline::Top), "    ");
+        assert_eq!(render(Baseline::Ratio(0.25)), "█   ");
+
+        let (top, bottom) = WaveformWidget::new(&[], &[]).split_ratio(0.7).halves(Rect::new(0, 0, 1, 10));
+        assert_eq!((top.rows, bottom.rows), (7, 3));
+    }
+
+    #[test]
+    fn test_render_single() {
+        let area = Rect::new(0, 0, 2, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[0.5, 1.0]).render(area, &mut buf);
+
+        let rows: Vec<String> = (0..2).map(|y| (0..2).map(|x| buf[(x, y)].symbol()).collect()).collect();
+        assert_eq!(rows, [" ⡇", "⡇⡇"]);
+    }
+
+    #[test]
+    fn test_render_alignment() {
+        let area = Rect::new(0, 0, 5, 2);
+        let render = |alignment| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::new(&[1.0, 0.5], &[])
+                .mode(WaveformMode::SolidBlock)
+                .align(alignment)
+                .render(area, &mut buf);
+            (0..5).map(|x| buf[(x, 0)].symbol()).collect::<String>()
+        };
+
+        assert_eq!(render(Alignment::Right), "   ██");
+        assert_eq!(render(Alignment::Left), "██   ");
+        assert_eq!(render(Alignment::Center), " ██  ");
+        assert_eq!(sample_index(Alignment::Left, 2, 5, 3), Some(1));
+        assert_eq!(sample_index(Alignment::Left, 2, 5, 2), None);
+    }
+
+    #[test]
+    fn test_render_offset() {
+        let data = [1.0, 0.5, 0.0, 0.0];
+        let area = Rect::new(0, 0, 2, 4);
+        let render = |offset| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::single(&data).mode(WaveformMode::SolidBlock).offset(offset).render(area, &mut buf);
+            (0..4).map(|y| (0..2).map(|x| buf[(x, y)].symbol()).collect()).collect::<Vec<String>>()
+        };
+
+        // Following the tail shows the newest samples, with no indicator.
+        assert_eq!(render(0), ["  ", "  ", "  ", "  "]);
+        // Scrolled back to the start: the indicator thumb sits on the left.
+        assert_eq!(render(2), ["█ ", "█ ", "██", "━█"]);
+    }
+
+    #[test]
+    fn test_render_fit_stretch() {
+        let area = Rect::new(0, 0, 5, 4);
+        let render = |fit| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::single(&[0.0, 1.0]).mode(WaveformMode::SolidBlock).fit(fit).render(area, &mut buf);
+            (0..5).map(|x| (0..4).filter(|&y| buf[(x, y)].symbol() == "█").count()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(render(Fit::Natural), [0, 0, 0, 0, 4]);
+        assert_eq!(render(Fit::Stretch), [0, 1, 2, 3, 4]);
+        assert_eq!(render(Fit::StretchNearest), [0, 0, 4, 4, 4]);
+    }
+
+    #[test]
+    fn test_rendered_columns() {
+        let area = Rect::new(0, 0, 3, 4);
+        let widget = WaveformWidget::new(&[0.5, 1.0], &[0.25])
+            .top_style(Style::default().fg(Color::Green))
+            .edge_style(Style::default().fg(Color::White));
+        let columns = widget.rendered_columns(area);
+
+        assert_eq!(columns.len(), 2);
+        assert_eq!((columns[0].x, columns[1].x), (1, 2));
+        let top = columns[0].top.unwrap();
+        assert_eq!((top.value, top.dots, top.style.fg), (0.5, 4, Some(Color::Rgb(255, 255, 255))));
+        assert_eq!(columns[1].top.unwrap().dots, 8);
+        assert_eq!(columns[1].bottom.unwrap().dots, 2);
+        assert!(columns[0].bottom.is_none());
+    }
+
+    #[test]
+    fn test_render_right_to_left() {
+        let area = Rect::new(0, 0, 4, 2);
+        let render = |phase| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::single(&[0.5, 1.0])
+                .direction(Direction::RightToLeft)
+                .scroll_phase(phase)
+                .fade_effect(true)
+                .top_style(Style::default().fg(Color::Rgb(200, 200, 200)))
+                .render(area, &mut buf);
+            buf
+        };
+
+        let buf = render(0.0);
+        let row: String = (0..4).map(|x| buf[(x, 1)].symbol()).collect();
+        // Newest on the left, in the right dot column; the fade dims the right edge.
+        assert_eq!(row, "⢸⢸  ");
+        assert_eq!(buf[(0, 1)].fg, Color::Rgb(200, 200, 200));
+
+        let buf = render(0.5);
+        let row: String = (0..4).map(|x| buf[(x, 1)].symbol()).collect();
+        assert_eq!(row, " ⡇⡇ ");
+    }
+
+    #[test]
+    fn test_render_baseline_axis() {
+        let area = Rect::new(0, 0, 3, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[], &[0.0, 0.5])
+            .mode(WaveformMode::SolidBlock)
+            .baseline_style(Style::default().fg(Color::Gray))
+            .render(area, &mut buf);
+
+        let row: String = (0..3).map(|x| buf[(x, 2)].symbol()).collect();
+        // The zero sample leaves the axis visible; the data covers it.
+        assert_eq!(row, "──█");
+        assert_eq!(buf[(0, 2)].fg, Color::Gray);
+    }
+
+    #[test]
+    fn test_time_weighted() {
+        // 1.0 for 3 time units, then a 1-unit blip of 0.0.
+        let buckets = time_weighted(&[1.0, 0.0, 0.5], &[0.0, 3.0, 4.0], 2);
+        assert_eq!(buckets, [(1.0, 0), (1.0 / 3.0, 2)]);
+        assert_eq!(time_weighted(&[0.5], &[2.0], 4), [(0.5, 0)]);
+    }
+
+    #[test]
+    fn test_time_weighted_last_sample() {
+        // The newest sample holds for the average interval (2 units) instead of not at all.
+        let buckets = time_weighted(&[1.0, 0.0, 1.0], &[0.0, 3.0, 4.0], 1);
+        assert_eq!(buckets, [(5.0 / 6.0, 0)]);
+        let buckets = time_weighted(&[0.0, 0.0, 1.0], &[0.0, 1.0, 2.0], 3);
+        assert_eq!(buckets, [(0.0, 0), (0.0, 1), (1.0, 2)]);
+    }
+
+    #[test]
+    fn test_time_weighted_invalid() {
+        let buckets = time_weighted(&[1.0, f64::NAN, 0.0, f64::INFINITY], &[0.0, 1.0, 2.0, 3.0], 2);
+        assert_eq!(buckets, [(1.0, 0), (0.0, 2)]);
+        let buckets = time_weighted(&[f64::NAN, f64::NAN, 2.0, 4.0], &[0.0, 1.0, 2.0, 3.0], 2);
+        assert!(buckets[0].0.is_nan());
+        assert_eq!(buckets[1], (3.0, 2));
+    }
+
+    #[test]
+    fn test_time_weighted_offset() {
+        let values = [1.0, 1.0, 0.0, 0.0];
+        let times = [0.0, 1.0, 2.0, 3.0];
+        let widget = WaveformWidget::new(&values, &[]).top_timestamps(&times[..3]).offset(1);
+        // The sample without a timestamp is dropped first, then the offset
+        // takes off the newest timestamped one.
+        let prepared = widget.prepare(&widget.top, 2);
+        assert_eq!(prepared.indices, [0, 1]);
+        assert_eq!(prepared.values, [1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_render_center_gap() {
+        let area = Rect::new(0, 0, 1, 5);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[1.0], &[1.0])
+            .mode(WaveformMode::SolidBlock)
+            .center_gap(1)
+            .baseline_style(Style::default())
+            .render(area, &mut buf);
+
+        let column: String = (0..5).map(|y| buf[(0, y)].symbol()).collect();
+        assert_eq!(column, "██─██");
+    }
+
+    #[test]
+    fn test_localize() {
+        let area = Rect::new(0, 0, 4, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[], &[])
+            .equalize(true)
+            .localize(|label| (label == Label::Equalized).then(|| "均".to_string()))
+            .render(area, &mut buf);
+        assert_eq!(buf[(0, 0)].symbol(), "均");
+
+        let widget = WaveformWidget::new(&[], &[]);
+        assert_eq!(widget.label(Label::Equalized), "EQ");
+
+        // Stat names and numbers go through the hook too.
+        let area = Rect::new(0, 0, 16, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[2.0, 4.0], &[])
+            .stats(&[Aggregate::MIN, Aggregate::new("p99", |_| 4.0)])
+            .localize(|label| match label {
+                Label::Min => Some("mín".to_string()),
+                Label::Value(value) => Some(format!("{value:.1}").replace('.', ",")),
+                _ => None,
+            })
+            .render(area, &mut buf);
+        assert!((0..16).map(|x| buf[(x, 0)].symbol()).collect::<String>().ends_with("mín 2,0 p99 4,0"));
+
+
+        // A custom aggregate named like a built-in one keeps its own name.
+        let widget = WaveformWidget::new(&[], &[]).localize(|label| (label == Label::Min).then(|| "mín".to_string()));
+        assert_eq!(widget.aggregate_name(&Aggregate::new("min", |_| 0.0)), "min");
+        assert_eq!(widget.aggregate_name(&Aggregate::MIN), "mín");
+    }
+
+    #[test]
+    fn test_downsample() {
+        let data = [0.0, 1.0, 0.5, 0.5, 0.2, 0.4];
+        let samples = Samples::Floats(Cow::Borrowed(&data[..]));
+        let values = |method| downsample(&samples, 2, method).iter().map(|p| p.value).collect::<Vec<_>>();
+
+        assert_eq!(values(Downsample::MinMax), [1.0, 0.5]);
+        assert_eq!(downsample(&samples, 2, Downsample::MinMax)[1].low, 0.2);
+        assert_eq!(values(Downsample::Average), [0.5, 0.3666666666666667]);
+        assert_eq!(values(Downsample::Rms)[0], (1.25f64 / 3.0).sqrt());
+
+        // LTTB keeps the endpoints and the spike.
+        let spike = [0.0, 0.1, 0.0, 0.9, 0.0, 0.1, 0.0];
+        let indices: Vec<usize> = lttb(&Samples::Floats(Cow::Borrowed(&spike[..])), 3).iter().map(|p| p.index).collect();
+        assert_eq!(indices, [0, 3, 6]);
+
+        let area = Rect::new(0, 0, 2, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&data).mode(WaveformMode::SolidBlock).downsample(Downsample::MinMax).render(area, &mut buf);
+        assert_eq!(buf[(0, 0)].symbol(), "█");
+        assert_eq!(buf[(1, 0)].symbol(), " ");
+    }
+
+    #[test]
+    fn test_downsample_offset() {
+        // The 3 newest samples are scrolled out of view and don't contribute.
+        let data = [0.2, 0.4, 0.6, 0.8, 1.0, 1.0, 1.0];
+        for method in [Downsample::MinMax, Downsample::Average, Downsample::Rms, Downsample::Lttb] {
+            let widget = WaveformWidget::single(&data).downsample(method).offset(3);
+            let prepared = widget.prepare(&widget.top, 3);
+            assert!(prepared.indices.iter().all(|&i| i < 4), "{method:?}: {:?}", prepared.indices);
+            assert!(prepared.values.iter().all(|&v| v < 0.9), "{method:?}: {:?}", prepared.values);
+        }
+    }
+
+    #[test]
+    fn test_on_render_column() {
+        let mut seen = Vec::new();
+        let area = Rect::new(0, 0, 3, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[0.1, 0.5, 1.0], &[])
+            .offset(1)
+            .on_render_column(|column| seen.push((column.x, column.top.map(|t| t.index))))
+            .render(area, &mut buf);
+
+        assert_eq!(seen, [(1, Some(0)), (2, Some(1))]);
+    }
+
+    #[test]
+    fn test_render_envelope() {
+        let area = Rect::new(0, 0, 3, 8);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::envelope(&[(-0.5, 1.0), (0.5, 1.0), (-1.0, -0.5)])
+            .mode(WaveformMode::SolidBlock)
+            .render(area, &mut buf);
+
+        let rows: Vec<String> = (0..8).map(|y| (0..3).map(|x| buf[(x, y)].symbol()).collect()).collect();
+        assert_eq!(rows, ["██ ", "██ ", "█  ", "█  ", "█  ", "█  ", "  █", "  █"]);
+    }
+
+    #[test]
+    fn test_render_gaps() {
+        let area = Rect::new(0, 0, 4, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[1.0, f64::NAN, 1.0, f64::NAN])
+            .mode(WaveformMode::SolidBlock)
+            .gap_style(Style::default().fg(Color::DarkGray))
+            .stale_style(Style::default().fg(Color::Red))
+            .render(area, &mut buf);
+
+        let row: String = (0..4).map(|x| buf[(x, 1)].symbol()).collect();
+        assert_eq!(row, "█┊█┊");
+        assert_eq!(buf[(1, 1)].fg, Color::DarkGray);
+        assert_eq!(buf[(3, 1)].fg, Color::Red);
+    }
+
+    #[test]
+    fn test_from_samples() {
+        let area = Rect::new(0, 0, 2, 2);
+        let render = |widget: WaveformWidget| {
+            let mut buf = Buffer::empty(area);
+            widget.mode(WaveformMode::SolidBlock).render(area, &mut buf);
+            buf
+        };
+
+        let floats = render(WaveformWidget::new(&[0.5, 1.0], &[1.0, 0.0]));
+        let converted = render(WaveformWidget::from_samples(&[0.5f32, 1.0], &[4u64, 0]).bottom_max(4.0));
+        assert_eq!(floats, converted);
+    }
+
+    #[test]
+    fn test_render_stacked_percent() {
+        let area = Rect::new(0, 0, 2, 8);
+        let red = Style::default().fg(Color::Red);
+        let render = |stacking| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::single(&[0.1, 0.5])
+                .mode(WaveformMode::SolidBlock)
+                .top_layers(&[(&[0.1, 0.0], red)])
+                .stacking(stacking)
+                .render(area, &mut buf);
+            (0..8).map(|y| (0..2).map(|x| buf[(x, y)].symbol()).collect()).collect::<Vec<String>>()
+        };
+
+        // 0.1 + 0.1 covers two rows of the first column.
+        assert_eq!(render(Stacking::Absolute)[4..], [" █", " █", "██", "██"]);
+        let percent = render(Stacking::Percent);
+        assert!(percent.iter().all(|row| row == "██"));
+    }
+
+    #[test]
+    fn test_prepared_once_per_render() {
+        struct Counted(std::cell::Cell<usize>);
+
+        impl SampleSource for Counted {
+            fn sample(&self, index: usize) -> f64 {
+                self.0.set(self.0.get() + 1);
+                index as f64
+            }
+
+            fn len(&self) -> usize {
+                8
+            }
+        }
+
+        let area = Rect::new(0, 0, 4, 4);
+        let (top, bottom) = (Counted(Default::default()), Counted(Default::default()));
+        let mut columns = 0;
+        WaveformWidget::from_sources(&top, &bottom)
+            .autoscale(0.1)
+            .hover(Some((3, 0)))
+            .on_render_column(|_| columns += 1)
+            .render(area, &mut Buffer::empty(area));
+
+        // The 4 samples in view once, plus the hovered one for its value.
+        assert_eq!(columns, 4);
+        assert_eq!((top.0.get(), bottom.0.get()), (5, 4));
+    }
+
+    #[test]
+    fn test_from_sources() {
+        struct Ramp(usize);
+
+        impl SampleSource for Ramp {
+            fn sample(&self, index: usize) -> f64 {
+                index as f64 / self.0 as f64
+            }
+
+            fn len(&self) -> usize {
+                self.0
+            }
+        }
+
+        let area = Rect::new(0, 0, 4, 4);
+        let (top, bottom) = (Ramp(8), Ramp(100_000));
+        let slices: Vec<Vec<f64>> = [&top, &bottom].iter().map(|r| (0..r.len()).map(|i| r.sample(i)).collect()).collect();
+        for method in [None, Some(Downsample::Lttb)] {
+            let render = |widget: WaveformWidget| {
+                let mut buf = Buffer::empty(area);
+                let widget = match method {
+                    Some(method) => widget.downsample(method),
+                    None => widget,
+                };
+                widget.render(area, &mut buf);
+                buf
+            };
+            let expected = render(WaveformWidget::new(&slices[0], &slices[1]));
+            assert_eq!(render(WaveformWidget::from_sources(&top, &bottom)), expected);
+        }
+    }
+
+    #[test]
+    fn test_invalid_policy() {
+        let area = Rect::new(0, 0, 4, 2);
+        let row = |invalid| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::single(&[f64::NAN, 1.0, f64::INFINITY, f64::NAN])
+                .mode(WaveformMode::SolidBlock)
+                .gap_style(Style::default())
+                .invalid(invalid)
+                .render(area, &mut buf);
+            (0..4).map(|x| buf[(x, 0)].symbol().to_string() + buf[(x, 1)].symbol()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(row(Invalid::Skip), [" ┊", "██", " ┊", " ┊"]);
+        assert_eq!(row(Invalid::Zero), ["  ", "██", "  ", "  "]);
+        assert_eq!(row(Invalid::HoldLast), [" ┊", "██", "██", "██"]);
+    }
+
+    #[test]
+    fn test_render_missing_samples() {
+        let area = Rect::new(0, 0, 3, 2);
+        let mut buf = Buffer::empty(area);
+        let data = [Some(1.0), None, Some(0.0)];
+        WaveformWidget::from_samples(&data, &[0.0; 0])
+            .baseline(Baseline::Bottom)
+            .mode(WaveformMode::SolidBlock)
+            .gap_style(Style::default())
+            .render(area, &mut buf);
+
+        let row: String = (0..3).map(|x| buf[(x, 1)].symbol()).collect();
+        assert_eq!(row, "█┊ ");
+    }
+
+    #[test]
+    fn test_missing_samples_policy() {
+        let area = Rect::new(0, 0, 3, 1);
+        let data = [Some(1.0), None, Some(f64::NAN)];
+        for invalid in [Invalid::Zero, Invalid::HoldLast] {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::from_samples(&data, &[0.0; 0])
+                .baseline(Baseline::Bottom)
+                .mode(WaveformMode::SolidBlock)
+                .gap_style(Style::default())
+                .invalid(invalid)
+                .render(area, &mut buf);
+
+            // The missing sample stays a gap; only the NaN one is filled in.
+            let row: String = (0..3).map(|x| buf[(x, 0)].symbol()).collect();
+            assert_eq!(row, if invalid == Invalid::Zero { "█┊ " } else { "█┊█" });
+        }
+    }
+
+    #[test]
+    fn test_soft_clip() {
+        let clip = SoftClip { threshold: 0.5, knee: 0.2, ratio: 2.0 };
+        assert_eq!(clip.apply(0.3), 0.3);
+        assert_eq!(clip.apply(0.5), 0.4875);
+        assert_eq!(clip.apply(1.0), 0.75);
+        assert_eq!(clip.apply(1.5), 1.0);
+
+        // An overshoot keeps its height difference instead of clamping flat.
+        let area = Rect::new(0, 0, 2, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[1.0, 1.5]).mode(WaveformMode::SolidBlock).soft_clip(0.5, 0.2, 2.0).render(area, &mut buf);
+        let column = |x| (0..4).map(|y| buf[(x, y)].symbol()).collect::<String>();
+        assert_eq!(column(0), " ███");
+        assert_eq!(column(1), "████");
+    }
+
+    #[test]
+    fn test_render_bipolar() {
+        let area = Rect::new(0, 0, 3, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::bipolar(&[1.0, -0.5, f64::NAN]).mode(WaveformMode::SolidBlock).render(area, &mut buf);
+
+        let column = |x| (0..4).map(|y| buf[(x, y)].symbol()).collect::<String>();
+        assert_eq!(column(0), "██  ");
+        assert_eq!(column(1), "  █ ");
+        assert_eq!(column(2), "    ");
+    }
+
+    #[test]
+    fn test_render_frame() {
+        let area = Rect::new(0, 0, 4, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[], &[]).frame_style(Style::default()).render(area, &mut buf);
+
+        let rows: Vec<String> = (0..4).map(|y| (0..4).map(|x| buf[(x, y)].symbol()).collect()).collect();
+        assert_eq!(rows, ["┌  ┐", "    ", "   ⡱", "└  ┘"]);
+
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[], &[])
+            .direction(Direction::RightToLeft)
+            .frame_style(Style::default())
+            .render(area, &mut buf);
+        assert_eq!(buf[(0, 2)].symbol(), "⢎");
+    }
+
+    #[test]
+    fn test_render_overview() {
+        let data: Vec<f64> = (0..40).map(|i| (i % 2) as f64).collect();
+        let area = Rect::new(0, 0, 10, 4);
+        let mut buf = Buffer::empty(area);
+        let highlight = Style::default().bg(Color::Blue);
+        WaveformWidget::single(&data).offset(10).overview(6, 3, highlight).render(area, &mut buf);
+
+        assert_eq!(buf[(0, 0)].symbol(), "┌");
+        assert_eq!(buf[(5, 2)].symbol(), "┘");
+        // 10 of 40 samples in view, ending 10 before the newest: the third
+        // of the overview's 4 columns.
+        let highlighted: Vec<u16> = (1..5).filter(|&x| buf[(x, 1)].bg == Color::Blue).collect();
+        assert_eq!(highlighted, [3]);
+        // The main chart still fills the columns right of the inset.
+        assert_ne!(buf[(9, 2)].symbol(), " ");
+    }
+
+    #[test]
+    fn test_render_stateful_scroll() {
+        use ratatui::widgets::StatefulWidget;
+
+        let area = Rect::new(0, 0, 4, 3);
+        let mut scroll = ScrollState::new();
+        let data: Vec<f64> = (0..12).map(|i| i as f64 / 11.0).collect();
+        let render = |len: usize, scroll: &mut ScrollState| {
+            let mut buf = Buffer::empty(area);
+            StatefulWidget::render(WaveformWidget::single(&data[..len]), area, &mut buf, scroll);
+            buf
+        };
+
+        // The chart, above the scroll indicator on the last row.
+        let chart = |buf: Buffer| (0..8).map(|i| buf[(i % 4, i / 4)].symbol().to_string()).collect::<String>();
+
+        render(8, &mut scroll);
+        scroll.scroll_back(2);
+        let paused = chart(render(8, &mut scroll));
+        assert_ne!(paused.trim(), "");
+        // New samples arrive, but the paused view holds still.
+        assert_eq!(chart(render(12, &mut scroll)), paused);
+        assert_eq!(scroll.offset(), 6);
+
+        scroll.follow();
+        let mut expected = Buffer::empty(area);
+        Widget::render(WaveformWidget::single(&data), area, &mut expected);
+        assert_eq!(render(12, &mut scroll), expected);
+    }
+
+    #[test]
+    fn test_glyph_fallback() {
+        let area = Rect::new(0, 0, 2, 2);
+        let render = |support| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::single(&[0.5, 1.0]).glyph_support(support).render(area, &mut buf);
+            (0..4).map(|i| buf[(i % 2, i / 2)].symbol().to_string()).collect::<String>()
+        };
+
+        assert_eq!(render(GlyphSupport::FULL), " ⡇⡇⡇");
+        assert_eq!(render(GlyphSupport::BLOCKS), " ███");
+        assert_eq!(render(GlyphSupport::ASCII), " ###");
+    }
+
+    #[test]
+    fn test_render_window() {
+        let data = [0.1, 0.2, 1.0, 0.0, 1.0, 0.3];
+        let area = Rect::new(0, 0, 3, 2);
+        let render = |widget: WaveformWidget| {
+            let mut buf = Buffer::empty(area);
+            widget.mode(WaveformMode::SolidBlock).render(area, &mut buf);
+            buf
+        };
+
+        assert_eq!(render(WaveformWidget::single(&data).window(2, 5)), render(WaveformWidget::single(&data[2..5])));
+        // The offset scrolls back within the window; the end is clamped to the data.
+        let scrolled = render(WaveformWidget::single(&data).window(1, 9).offset(2));
+        let expected = render(WaveformWidget::single(&data[1..4]));
+        assert_eq!(scrolled.content[..3], expected.content[..3]);
+    }
+
+    #[test]
+    fn test_dead_band() {
+        let area = Rect::new(0, 0, 5, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[0.5, 0.6, f64::NAN, 0.45, 1.0])
+            .mode(WaveformMode::SolidBlock)
+            .top_dead_band(0.2)
+            .render(area, &mut buf);
+
+        let heights: Vec<usize> = (0..5).map(|x| (0..4).filter(|&y| buf[(x, y)].symbol() == "█").count()).collect();
+        assert_eq!(heights, [2, 2, 0, 2, 4]);
+    }
+
+    #[test]
+    fn test_dead_band_anchor() {
+        use ratatui::widgets::StatefulWidget;
+        let area = Rect::new(0, 0, 2, 4);
+        // Filled cells, the scroll indicator included.
+        let heights =
+            |buf: &Buffer| (0..2).map(|x| (0..4).filter(|&y| buf[(x, y)].symbol() != " ").count()).collect::<Vec<_>>();
+        let data = [0.5, 0.7, 0.3, 1.0, 0.8];
+        let widget = |data| WaveformWidget::single(data).mode(WaveformMode::SolidBlock).top_dead_band(0.3);
+
+        // Scrolled back, 0.3 is still held at the 0.5 from before the view.
+        let mut buf = Buffer::empty(area);
+        Widget::render(widget(&data).offset(2), area, &mut buf);
+        assert_eq!(heights(&buf), [2, 2]);
+
+        // The state carries the held value over the samples dropped since.
+        let mut state = ScrollState::new();
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(widget(&data[..3]), area, &mut buf, &mut state);
+        assert_eq!(heights(&buf), [2, 2]);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(widget(&[0.3, 0.4]).first_sample(2, 0), area, &mut buf, &mut state);
+        assert_eq!(heights(&buf), [2, 2]);
+
+        // Held over gaps up to the look-back, not past it.
+        let held = |gaps| {
+            let data: Vec<f64> = [0.5].into_iter().chain(vec![f64::NAN; gaps]).chain([0.3, 0.3]).collect();
+            let mut buf = Buffer::empty(area);
+            let widget = WaveformWidget::single(&data).mode(WaveformMode::SolidBlock).top_dead_band(0.3);
+            Widget::render(widget, area, &mut buf);
+            heights(&buf)
+        };
+        assert_eq!(held(LOOK_BACK - 1), [2, 2]);
+        assert_eq!(held(LOOK_BACK), [1, 1]);
+
+        // Without it, the band starts over at the first sample left.
+        let mut buf = Buffer::empty(area);
+        Widget::render(widget(&[0.3, 0.4]).first_sample(2, 0), area, &mut buf);
+        assert_eq!(heights(&buf), [1, 1]);
+    }
+
+    #[test]
+    fn test_zoom() {
+        let data = [0.0, 1.0, 0.0, 0.5, 0.0, 0.5];
+        let area = Rect::new(0, 0, 3, 2);
+        let heights = |widget: WaveformWidget| {
+            let mut buf = Buffer::empty(area);
+            widget.mode(WaveformMode::SolidBlock).render(area, &mut buf);
+            (0..3).map(|x| (0..2).filter(|&y| buf[(x, y)].symbol() == "█").count()).collect::<Vec<_>>()
+        };
+
+        // Two samples per column, peak of each pair.
+        assert_eq!(heights(WaveformWidget::single(&data).zoom(0.5)), [2, 1, 1]);
+        // Each sample over two columns: the newest 2 samples in 3 columns.
+        assert_eq!(heights(WaveformWidget::single(&data).zoom(2.0)), [0, 0, 1]);
+        // Scrolled back; the bottom row holds the scroll indicator.
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&data).mode(WaveformMode::SolidBlock).zoom(2.0).offset(3).render(area, &mut buf);
+        assert_eq!((0..3).map(|x| buf[(x, 0)].symbol()).collect::<String>(), "██ ");
+    }
+
+    #[test]
+    fn test_intensity() {
+        let area = Rect::new(0, 0, 1, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[1.0], &[1.0])
+            .mode(WaveformMode::SolidBlock)
+            .top_style(Style::default().fg(Color::Rgb(200, 100, 50)))
+            .bottom_style(Style::default().fg(Color::Rgb(200, 100, 50)))
+            .bottom_intensity(0.5)
+            .render(area, &mut buf);
+
+        assert_eq!(buf[(0, 0)].fg, Color::Rgb(200, 100, 50));
+        assert_eq!(buf[(0, 1)].fg, Color::Rgb(100, 50, 25));
+    }
+
+    #[test]
+    fn test_flush_edge() {
+        let area = Rect::new(0, 0, 2, 1);
+        let render = |direction| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::single(&[1.0, 1.0]).direction(direction).flush_edge(true).render(area, &mut buf);
+            (0..2).map(|x| buf[(x, 0)].symbol()).collect::<String>()
+        };
+
+        assert_eq!(render(Direction::LeftToRight), "⡇⢸");
+        assert_eq!(render(Direction::RightToLeft), "⡇⢸");
+    }
+
+    #[test]
+    fn test_scale() {
+        let db = Scale::Db { floor: -60.0 };
+        assert_eq!(db.apply(1.0), 1.0);
+        assert!((db.apply(0.001) - 0.0).abs() < 1e-12);
+        assert!((db.apply(0.1) - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(db.apply(0.0), 0.0);
+        assert_eq!(db.apply(1e-9), 0.0);
+        assert_eq!(Scale::Log { decades: 2.0 }.apply(0.1), 0.5);
+
+        // -20 dB is invisible on a linear 3-row scale, two thirds high in dB.
+        let area = Rect::new(0, 0, 1, 3);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[0.1]).mode(WaveformMode::SolidBlock).scale(db).render(area, &mut buf);
+        assert_eq!((0..3).map(|y| buf[(0, y)].symbol()).collect::<String>(), " ██");
+    }
+
+    #[test]
+    fn test_autoscale() {
+        let area = Rect::new(0, 0, 2, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[5.0, 10.0])
+            .mode(WaveformMode::SolidBlock)
+            .top_markers(&[Marker::new(10.0, "")])
+            .autoscale(1.0)
+            .render(area, &mut buf);
+
+        let heights: Vec<usize> = (0..2).map(|x| (0..4).filter(|&y| buf[(x, y)].symbol() == "█").count()).collect();
+        assert_eq!(heights, [1, 2]);
+        // Markers follow the scale.
+        assert_eq!(buf[(0, 1)].symbol(), "╌");
+    }
+
+    #[test]
+    fn test_autoscale_decay() {
+        let data = [8.0, 1.0, 1.0, 2.0];
+        let max = |widget: WaveformWidget| widget.prepare(&widget.top, 2).max;
+
+        // In view: 1.0 and 2.0. The spike two samples before them counts for
+        // 8 * 0.5^2 = 2 at a decay of 0.5, for 8 * 0.9^2 at 0.9.
+        assert_eq!(max(WaveformWidget::single(&data).autoscale(0.0)), 2.0);
+        assert_eq!(max(WaveformWidget::single(&data).autoscale(0.0).autoscale_decay(0.5)), 2.0);
+        assert_eq!(max(WaveformWidget::single(&data).autoscale(0.0).autoscale_decay(0.9)), 8.0 * 0.9f64.powi(2));
+        // Scrolled back, the spike is one sample away.
+        assert_eq!(max(WaveformWidget::single(&data).autoscale(0.0).autoscale_decay(0.5).offset(1)), 4.0);
+
+        // Spikes past the look-back are not read, even without decay.
+        let mut data = vec![1.0; LOOK_BACK + 3];
+        data[0] = 8.0;
+        assert_eq!(max(WaveformWidget::single(&data).autoscale(0.0).autoscale_decay(1.0)), 1.0);
+        data[1] = 8.0;
+        assert_eq!(max(WaveformWidget::single(&data).autoscale(0.0).autoscale_decay(1.0)), 8.0);
+    }
+
+    #[test]
+    fn test_burn_in_protection() {
+        let area = Rect::new(0, 0, 3, 3);
+        let render = |uptime| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::single(&[1.0, 1.0])
+                .mode(WaveformMode::SolidBlock)
+                .burn_in_protection(true)
+                .frame_time(Duration::from_secs(uptime))
+                .render(area, &mut buf);
+            (0..3).map(|y| (0..3).map(|x| buf[(x, y)].symbol()).collect::<String>()).collect::<Vec<_>>()
+        };
+        assert_eq!(render(0), ["██ ", "██ ", "   "]);
+        assert_eq!(render(300), [" ██", " ██", "   "]);
+        assert_eq!(render(600), ["   ", " ██", " ██"]);
+        // Back to the start after four steps.
+        assert_eq!(render(1200), render(0));
+    }
+
+    #[test]
+    fn test_min_hold() {
+        let area = Rect::new(0, 0, 5, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[1.0, 0.0, 1.0, f64::NAN, 1.0])
+            .mode(WaveformMode::SolidBlock)
+            .top_min_hold(0.25, Style::default().fg(Color::Red))
+            .render(area, &mut buf);
+
+        // The floor drops to the dip, then rises a quarter per column (gaps included).
+        let rows: Vec<Option<u16>> = (0..5).map(|x| (0..4).find(|&y| buf[(x, y)].symbol() == "─")).collect();
+        assert_eq!(rows, [Some(0), Some(3), Some(2), Some(1), Some(0)]);
+        assert_eq!(buf[(1, 3)].fg, Color::Red);
+    }
+
+    #[test]
+    fn test_autoscale_percentile() {
+        assert_eq!(percentile_of(&[3.0, 1.0, f64::NAN, 2.0, 100.0], 75.0), 3.0);
+        assert_eq!(percentile_of(&[3.0, 1.0, 2.0], 0.0), 1.0);
+        assert_eq!(percentile_of(&[], 99.0), 0.0);
+
+        let area = Rect::new(0, 0, 4, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[1.0, 2.0, 100.0, 2.0])
+            .mode(WaveformMode::SolidBlock)
+            .autoscale_percentile(75.0, 0.0)
+            .render(area, &mut buf);
+        // Scaled to 2.0: the spike is clamped instead of flattening the rest.
+        let heights: Vec<usize> = (0..4).map(|x| (0..2).filter(|&y| buf[(x, y)].symbol() == "█").count()).collect();
+        assert_eq!(heights, [1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn test_clip_style() {
+        let area = Rect::new(0, 0, 3, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[0.5, 1.0, 3.0], &[2.0])
+            .mode(WaveformMode::UltraThinBlock)
+            .clip_style(Style::default().fg(Color::Red))
+            .render(area, &mut buf);
+
+        // Only samples above the max are flagged, at the tip of their half.
+        assert_eq!(buf[(2, 0)].symbol(), "▌");
+        assert_eq!(buf[(2, 0)].fg, Color::Red);
+        assert_eq!(buf[(1, 0)].fg, Color::Reset);
+        assert_eq!(buf[(2, 3)].fg, Color::Red);
+        assert_eq!(buf[(2, 1)].fg, Color::Reset);
+    }
+
+    #[test]
+    fn test_axis_labels() {
+        let rows = |widget: WaveformWidget, width| {
+            let area = Rect::new(0, 0, width, 4);
+            let mut buf = Buffer::empty(area);
+            widget.mode(WaveformMode::SolidBlock).render(area, &mut buf);
+            (0..4).map(|y| (0..width).map(|x| buf[(x, y)].symbol()).collect::<String>()).collect::<Vec<_>>()
+        };
+
+        // Shared unit: both scales in the left gutter.
+        let shared = WaveformWidget::new(&[100.0], &[1.25]).top_max(100.0).bottom_max(2.5).axis_labels("%", "%");
+        assert_eq!(rows(shared, 6), ["100% █", "  0% █", "  0% █", "2.5%  "]);
+
+        // Different units: the bottom scale moves to a right gutter.
+        let dual = WaveformWidget::new(&[100.0], &[2.5]).top_max(100.0).bottom_max(2.5).axis_labels("%", "MB");
+        assert_eq!(rows(dual, 12), ["100% █      ", "  0% █      ", "     █ 0MB  ", "     █ 2.5MB"]);
+
+        // A custom formatter replaces the number and unit.
+        let formatted = WaveformWidget::new(&[1.0], &[0.5]).axis_format(|v| format!("{:.0}%", v * 100.0));
+        assert_eq!(rows(formatted, 6), ["100% █", "  0% █", "  0% █", "100%  "]);
+    }
+
+    #[test]
+    fn test_range() {
+        let area = Rect::new(0, 0, 4, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[20.0, 45.0, 75.0, 90.0])
+            .mode(WaveformMode::SolidBlock)
+            .top_range(30.0, 90.0)
+            .top_markers(&[Marker::new(60.0, "")])
+            .render(area, &mut buf);
+
+        // Below the min is empty, the rest maps onto 30 - 90.
+        let heights: Vec<usize> = (0..4).map(|x| (0..4).filter(|&y| buf[(x, y)].symbol() == "█").count()).collect();
+        assert_eq!(heights, [0, 1, 3, 4]);
+        assert_eq!(buf[(0, 1)].symbol(), "╌");
+    }
+
+    #[test]
+    fn test_stats() {
+        let area = Rect::new(0, 0, 30, 2);
+        let mut buf = Buffer::empty(area);
+        let spread = Aggregate::new("spread", |data| (Aggregate::MAX.fold)(data) - (Aggregate::MIN.fold)(data));
+        WaveformWidget::new(&[0.0, 0.5, f64::NAN, 1.0, 0.5], &[])
+            .stats(&[Aggregate::MIN, Aggregate::AVG, spread])
+            .render(area, &mut buf);
+
+        // Gaps are left out; the empty bottom half shows nothing.
+        let row = |y| (0..30).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        assert!(row(0).ends_with("min 0 avg 0.5 spread 1"));
+        assert_eq!(row(1).trim(), "");
+    }
+
+    #[test]
+    fn test_show_stats() {
+        let area = Rect::new(0, 0, 30, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[], &[0.0, 0.5, 1.0]).show_stats(true).render(area, &mut buf);
+
+        // The default min, max and avg of the bottom half.
+        assert!((0..30).map(|x| buf[(x, 1)].symbol()).collect::<String>().ends_with("min 0 max 1 avg 0.5"));
+    }
+
+    #[test]
+    fn test_gradient_stops() {
+        let area = Rect::new(0, 0, 1, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[1.0])
+            .mode(WaveformMode::SolidBlock)
+            .gradient(&[(1.0, Color::Red), (0.0, Color::Green), (0.7, Color::Yellow)])
+            .color_depth(ColorDepth::Ansi16)
+            .render(area, &mut buf);
+
+        let colors: Vec<Color> = (0..4).map(|y| buf[(0, y)].fg).collect();
+        // 16 colors snap each row to the nearest stop.
+        assert_eq!(colors, [Color::Red, Color::Yellow, Color::Yellow, Color::Green]);
+    }
+
+    #[test]
+    fn test_hysteresis() {
+        use ratatui::widgets::StatefulWidget;
+
+        let area = Rect::new(0, 0, 1, 4);
+        let mut scroll = ScrollState::new();
+        let mut height = |value: f64, hysteresis: bool| {
+            let mut buf = Buffer::empty(area);
+            let data = [value];
+            let widget = WaveformWidget::single(&data).mode(WaveformMode::SolidBlock).hysteresis(hysteresis);
+            StatefulWidget::render(widget, area, &mut buf, &mut scroll);
+            (0..4).filter(|&y| buf[(0, y)].symbol() == "█").count()
+        };
+
+        assert_eq!(height(0.6, true), 2);
+        // 2.6 rows would round up, but stays within a row of the last frame.
+        assert_eq!(height(0.65, true), 2);
+        assert_eq!(height(0.8, true), 3);
+        assert_eq!(height(0.65, false), 3);
+    }
+
+    #[test]
+    fn test_hysteresis_dropped_samples() {
+        use ratatui::widgets::StatefulWidget;
+
+        let area = Rect::new(0, 0, 1, 4);
+        let mut scroll = ScrollState::new();
+        let mut height = |value: f64, first: usize| {
+            let mut buf = Buffer::empty(area);
+            let data = [value];
+            let widget = WaveformWidget::single(&data).mode(WaveformMode::SolidBlock).hysteresis(true);
+            StatefulWidget::render(widget.first_sample(first, 0), area, &mut buf, &mut scroll);
+            (0..4).filter(|&y| buf[(0, y)].symbol() == "█").count()
+        };
+
+        assert_eq!(height(0.6, 0), 2);
+        assert_eq!(height(0.65, 0), 2);
+        // Same index, but a new sample once the first one is dropped: nothing is held.
+        assert_eq!(height(0.65, 1), 3);
+    }
+
+    #[test]
+    fn test_style_fn() {
+        let area = Rect::new(0, 0, 3, 2);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[0.5, 1.0, 0.5], &[1.0])
+            .mode(WaveformMode::SolidBlock)
+            .top_style(Style::default().fg(Color::Rgb(0, 200, 0)))
+            .top_style_fn(|index, value| match (index, value) {
+                (0, _) => Style::default().bg(Color::Blue),
+                (_, v) if v > 0.8 => Style::default().fg(Color::Rgb(200, 0, 0)),
+                _ => Style::default(),
+            })
+            .render(area, &mut buf);
+
+        assert_eq!((buf[(0, 0)].fg, buf[(0, 0)].bg), (Color::Rgb(0, 200, 0), Color::Blue));
+        assert_eq!(buf[(1, 0)].fg, Color::Rgb(200, 0, 0));
+        assert_eq!(buf[(2, 0)].fg, Color::Rgb(0, 200, 0));
+        // The bottom half has no callback.
+        assert_eq!(buf[(2, 1)].fg, Color::Reset);
+    }
+
+    #[test]
+    fn test_axis_tick_density() {
+        assert_eq!(nice_step(0.3), 0.5);
+        assert_eq!(nice_step(20.0), 20.0);
+        assert_eq!(nice_step(33.3), 50.0);
+
+        let labels = |rows: u16| {
+            let area = Rect::new(0, 0, 8, rows);
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::single(&[]).top_max(100.0).axis_labels("", "").render(area, &mut buf);
+            (0..rows)
+                .map(|y| (0..4).map(|x| buf[(x, y)].symbol()).collect::<String>().trim().to_string())
+                .filter(|label| !label.is_empty())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels(3), ["100", "0"]);
+        assert_eq!(labels(7), ["100", "50", "0"]);
+        assert_eq!(labels(12), ["100", "80", "60", "40", "20", "0"]);
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let thresholds = [(0.8, Style::default().fg(Color::Red)), (0.5, Style::default().fg(Color::Yellow))];
+        let widget = WaveformWidget::single(&[0.2, 0.6, 0.9]).top_thresholds(&thresholds);
+        let styles = widget.prepare(&widget.top, 3).styles;
+        assert_eq!(styles.iter().map(|s| s.fg).collect::<Vec<_>>(), [None, Some(Color::Yellow), Some(Color::Red)]);
+    }
+
+    #[test]
+    fn test_fade_curve() {
+        let factors = |widget: WaveformWidget| (0..4).map(|x| widget.fade_factor(x, 4)).collect::<Vec<_>>();
+        let widget = || WaveformWidget::single(&[]).fade_effect(true);
+
+        assert_eq!(factors(widget()), [0.0, 0.5, 1.0, 1.0]);
+        assert_eq!(factors(widget().fade_start(0.0)), [0.0, 0.25, 0.5, 0.75]);
+        assert_eq!(factors(widget().fade_start(0.0).fade_curve(FadeCurve::Pow(2.0))), [0.0, 0.0625, 0.25, 0.5625]);
+        assert_eq!(factors(widget().fade_curve(FadeCurve::Custom(|t| 1.0 - t))), [1.0, 0.5, 0.0, 1.0]);
+        assert_eq!(factors(widget().fade_start(1.0)), [1.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_fade_start_full() {
+        let area = Rect::new(0, 0, 4, 2);
+        let mut buf = Buffer::empty(area);
+        let gray = Style::default().fg(Color::Rgb(200, 200, 200));
+        WaveformWidget::single(&[1.0; 4])
+            .mode(WaveformMode::SolidBlock)
+            .top_style(gray)
+            .fade_effect(true)
+            .fade_start(1.0)
+            .render(area, &mut buf);
+
+        // Nothing is faded, the oldest column included.
+        assert!((0..4).all(|x| buf[(x, 0)].fg == Color::Rgb(200, 200, 200)));
+    }
+
+    #[test]
+    fn test_style_palette() {
+        let data = [0.1, 0.9, f64::NAN, 0.4, 1.2, 0.7, 0.3, 0.8];
+        let area = Rect::new(0, 0, 10, 6);
+        let render = |palette: bool| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::new(&data, &data)
+                .top_style(Style::default().fg(Color::Rgb(200, 100, 50)))
+                .fade_effect(true)
+                .gradient_effect(true)
+                .reflection_effect(true)
+                .top_underlay(&data)
+                .gap_style(Style::default().fg(Color::DarkGray))
+                .clip_style(Style::default().bg(Color::Red))
+                .top_min_hold(0.1, Style::default().fg(Color::Blue))
+                .scroll_phase(0.5)
+                .style_palette(palette)
+                .render(area, &mut buf);
+            buf
+        };
+        assert_eq!(render(true), render(false));
+
+        let mut palette = Palette::new(Rect::new(0, 0, 2, 1));
+        let mut buf = Buffer::empty(Rect::new(0, 0, 2, 1));
+        for x in 0..2 {
+            palette.set(&mut buf, (x, 0), (Style::default(), 0, 1, false, 0), || Style::default().fg(Color::Red));
+        }
+        // One style for both cells, written by the flush.
+        assert_eq!(palette.styles.len(), 1);
+        assert_eq!(buf[(0, 0)].fg, Color::Reset);
+        palette.flush(&mut buf);
+        assert_eq!((buf[(0, 0)].fg, buf[(1, 0)].fg), (Color::Red, Color::Red));
+    }
+
+    #[test]
+    fn test_background_style() {
+        let data = [0.5];
+        let area = Rect::new(0, 0, 2, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&data, &[])
+            .mode(WaveformMode::BrailleLine)
+            .align(Alignment::Left)
+            .top_style(Style::default().fg(Color::Green))
+            .background_style(Style::default().bg(Color::Black))
+            .under_curve_style(Style::default().bg(Color::DarkGray))
+            .render(area, &mut buf);
+
+        // Top half: rows 1 (line) and 0 (empty below the line, still under the curve).
+        assert_eq!(buf[(0, 1)].bg, Color::DarkGray);
+        assert_eq!(buf[(0, 1)].symbol(), " ");
+        assert_eq!((buf[(0, 0)].bg, buf[(0, 0)].fg), (Color::DarkGray, Color::Rgb(0, 170, 0)));
+        assert_ne!(buf[(0, 0)].symbol(), " ");
+        // Everything else keeps the background.
+        assert_eq!(buf[(1, 0)].bg, Color::Black);
+        assert_eq!(buf[(0, 3)].bg, Color::Black);
+    }
+
+    #[test]
+    fn test_gradient_direction() {
+        let area = Rect::new(0, 0, 1, 4);
+        let brightness = |direction| {
+            let mut buf = Buffer::empty(area);
+            WaveformWidget::single(&[1.0])
+                .mode(WaveformMode::SolidBlock)
+                .top_style(Style::default().fg(Color::Rgb(0, 200, 0)))
+                .gradient_effect(true)
+                .gradient_direction(direction)
+                .render(area, &mut buf);
+            (0..4)
+                .map(|y| match buf[(0, y)].fg {
+                    Color::Rgb(_, g, _) => g,
+                    color => panic!("unexpected {color:?}"),
+                })
+                .collect::<Vec<_>>()
+        };
+        // Rows listed from the peak (top) down to the baseline.
+        assert_eq!(brightness(GradientDirection::BrightCenter), [95, 130, 165, 200]);
+        assert_eq!(brightness(GradientDirection::BrightPeaks), [200, 165, 130, 95]);
+    }
+
+    #[test]
+    fn test_gradient_strength() {
+        let area = Rect::new(0, 0, 1, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[1.0])
+            .mode(WaveformMode::SolidBlock)
+            .top_style(Style::default().fg(Color::Rgb(0, 200, 0)))
+            .gradient_effect(true)
+            .gradient_strength(0.2)
+            .render(area, &mut buf);
+        let colors: Vec<Color> = (0..4).map(|y| buf[(0, y)].fg).collect();
+        // 15% dimmer at the top row (3/4 of the way up) instead of 52.5%.
+        assert_eq!(colors[0], Color::Rgb(0, 170, 0));
+        assert_eq!(colors[3], Color::Rgb(0, 200, 0));
+    }
+
+    #[test]
+    fn test_value_gradient() {
+        let area = Rect::new(0, 0, 3, 4);
+        let mut buf = Buffer::empty(area);
+        let (blue, red) = (Color::Rgb(0, 0, 255), Color::Rgb(255, 0, 0));
+        WaveformWidget::single(&[0.0, 0.5, 1.0])
+            .mode(WaveformMode::SolidBlock)
+            .value_gradient(&[(1.0, red), (0.0, blue)])
+            .gradient(&[(0.0, Color::Green), (1.0, Color::Yellow)])
+            .render(area, &mut buf);
+
+        // Every row of a column shares its value's color.
+        assert_eq!(buf[(1, 3)].fg, Color::Rgb(128, 0, 128));
+        assert_eq!(buf[(1, 2)].fg, Color::Rgb(128, 0, 128));
+        assert_eq!(buf[(2, 3)].fg, red);
+        assert_eq!(buf[(2, 0)].fg, red);
+    }
+
+    #[test]
+    fn test_grid() {
+        let area = Rect::new(0, 0, 4, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[0.0, 0.0, 0.0, 100.0])
+            .mode(WaveformMode::SolidBlock)
+            .top_range(0.0, 100.0)
+            .grid(50.0, Style::default().fg(Color::DarkGray))
+            .separators(2)
+            .render(area, &mut buf);
+
+        // A line at 50 beneath the separator and the data.
+        let row = |y| (0..4).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        assert_eq!(row(1), "┈┈│█");
+        assert_eq!(row(2), "  │█");
+        assert_eq!(buf[(0, 1)].fg, Color::DarkGray);
+
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[]).top_range(0.0, 100.0).grid(50.0, Style::default()).grid_dots(true).render(area, &mut buf);
+        assert_eq!(buf[(0, 1)].symbol(), "⠒");
+    }
+
+    #[test]
+    fn test_grid_tiny_step() {
+        let area = Rect::new(0, 0, 2, 4);
+        let mut buf = Buffer::empty(area);
+        // A step far below one row (and below the float spacing at the max) still terminates.
+        WaveformWidget::single(&[]).top_range(0.0, 1e20).grid(1e-3, Style::default()).render(area, &mut buf);
+        let rows: Vec<String> = (0..4).map(|y| (0..2).map(|x| buf[(x, y)].symbol()).collect()).collect();
+        assert_eq!(rows, ["┈┈", "┈┈", "┈┈", "  "]);
+    }
+
+    #[test]
+    fn test_reference_lines() {
+        let area = Rect::new(0, 0, 8, 4);
+        let mut buf = Buffer::empty(area);
+        let red = Style::default().fg(Color::Red);
+        WaveformWidget::new(&[], &[])
+            .top_reference_lines(&[(0.5, red, Some("-6 dB"))])
+            .bottom_reference_lines(&[(0.5, Style::default(), None)])
+            .render(area, &mut buf);
+
+        let row = |y| (0..8).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        assert_eq!(row(0), "╌╌╌-6 dB");
+        assert_eq!(buf[(0, 0)].fg, Color::Red);
+        assert_eq!(row(3), "╌╌╌╌╌╌╌╌");
+    }
+
+    #[test]
+    fn test_reference_lines_both_halves() {
+        let area = Rect::new(0, 0, 8, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[], &[]).reference_lines(&[(0.5, Style::default(), Some("-6 dB"))]).render(area, &mut buf);
+
+        let row = |y| (0..8).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        assert_eq!(row(0), "╌╌╌-6 dB");
+        assert_eq!(row(3), "╌╌╌-6 dB");
+    }
+
+    #[test]
+    fn test_regions() {
+        let area = Rect::new(0, 0, 6, 2);
+        let mut buf = Buffer::empty(area);
+        let tint = Style::default().bg(Color::Blue);
+        WaveformWidget::new(&[0.0, 1.0, 1.0, 0.0, 0.5, 1.0], &[])
+            .regions(&[(1..3, tint), (2..4, Style::default().bg(Color::Red))])
+            .render(area, &mut buf);
+
+        let backgrounds: Vec<Color> = (0..6).map(|x| buf[(x, 1)].bg).collect();
+        assert_eq!(backgrounds, [Color::Reset, Color::Blue, Color::Red, Color::Red, Color::Reset, Color::Reset]);
+        // The waveform is drawn over the tint.
+        assert_ne!(buf[(1, 0)].symbol(), " ");
+        assert_eq!(buf[(1, 0)].bg, Color::Blue);
+    }
+
+    #[test]
+    fn test_show_last_value() {
+        let area = Rect::new(0, 0, 8, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::new(&[10.0, 42.0, f64::NAN], &[0.25])
+            .top_max(100.0)
+            .axis_format(|v| format!("{v:.0}%"))
+            .show_last_value(true)
+            .render(area, &mut buf);
+
+        let row = |y| (0..8).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        // The newest real sample, level with its column; the gap is skipped.
+        assert!(row(1).ends_with("42%"));
+        assert!(row(2).ends_with("0%"));
+    }
+
+    #[test]
+    fn test_cursor() {
+        use ratatui::widgets::StatefulWidget;
+
+        let area = Rect::new(0, 0, 8, 4);
+        let top = [0.0, 0.0, 30.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let render = |widget: WaveformWidget, state: &mut ScrollState| {
+            let mut buf = Buffer::empty(area);
+            StatefulWidget::render(widget.mode(WaveformMode::SolidBlock).top_max(100.0), area, &mut buf, state);
+            buf
+        };
+        let mut state = ScrollState::new();
+        state.set_cursor(Some(2));
+        let buf = render(WaveformWidget::single(&top), &mut state);
+
+        let row = |y| (0..8).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        // The line fills the blank cells of the column, the callout sits beside it.
+        assert_eq!(row(0), "  │30   ");
+        assert_eq!(buf[(2, 0)].fg, Color::Gray);
+        assert_eq!(row(3), "  █     ");
+
+        // Near the right edge, the callout moves to the left of the line.
+        let buf = render(WaveformWidget::single(&top).cursor(Some(7)), &mut ScrollState::new());
+        assert_eq!(buf[(6, 0)].symbol(), "0");
+        // Out of view, nothing is drawn.
+        state.set_cursor(Some(8));
+        let plain = render(WaveformWidget::single(&top), &mut ScrollState::new());
+        assert_eq!(render(WaveformWidget::single(&top), &mut state), plain);
+    }
+
+    #[test]
+    fn test_hit_test() {
+        let area = Rect::new(0, 0, 4, 4);
+        let top = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let bottom = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let widget = WaveformWidget::new(&top, &bottom).mode(WaveformMode::SolidBlock).offset(1);
+
+        // The newest sample in view is the fifth one, one is scrolled out.
+        assert_eq!(widget.hit_test(area, (3, 0)), Some(Hit { series: Side::Top, index: 4, value: 50.0 }));
+        assert_eq!(widget.hit_test(area, (0, 3)), Some(Hit { series: Side::Bottom, index: 1, value: 2.0 }));
+        assert_eq!(widget.hit_test(area, (4, 0)), None);
+
+        // Columns between bars hold no data.
+        let bars = WaveformWidget::new(&top, &bottom).mode(WaveformMode::SolidBlock).bar_width(1).bar_gap(1);
+        assert_eq!(bars.hit_test(area, (3, 1)).map(|hit| hit.index), Some(5));
+        assert_eq!(bars.hit_test(area, (2, 1)), None);
+
+        // Vertical charts grow rightwards from the center, newest sample at the bottom.
+        let vertical = WaveformWidget::new(&top, &bottom).mode(WaveformMode::SolidBlock).orientation(Orientation::Vertical);
+        assert_eq!(vertical.hit_test(area, (3, 3)), Some(Hit { series: Side::Top, index: 5, value: 60.0 }));
+        assert_eq!(vertical.hit_test(area, (0, 2)).map(|hit| (hit.series, hit.index)), Some((Side::Bottom, 4)));
+    }
+
+    #[test]
+    fn test_hit_test_with() {
+        use ratatui::widgets::StatefulWidget;
+
+        let area = Rect::new(0, 0, 4, 2);
+        let data: Vec<f64> = (0..20).map(|v| v as f64).collect();
+        let widget = || WaveformWidget::single(&data).mode(WaveformMode::SolidBlock);
+        let mut state = ScrollState::new();
+        state.zoom_out_at(0);
+        state.scroll_back(3);
+        StatefulWidget::render(widget(), area, &mut Buffer::empty(area), &mut state);
+
+        // 5 samples over 4 columns, ending 3 samples before the newest.
+        let chart = widget();
+        assert_eq!(chart.hit_test_with(&state, area, (3, 1)).map(|hit| hit.index), Some(16));
+        assert_eq!(chart.hit_test_with(&state, area, (0, 1)).map(|hit| hit.index), Some(12));
+        assert_eq!(chart.hit_test(area, (3, 1)).map(|hit| hit.index), Some(19));
+    }
+
+    #[test]
+    fn test_peak_hold() {
+        let area = Rect::new(0, 0, 3, 4);
+        let mut buf = Buffer::empty(area);
+        WaveformWidget::single(&[0.25])
+            .mode(WaveformMode::SolidBlock)
+            .top_peak_hold(0.75, Style::default().fg(Color::Gray))
+            .render(area, &mut buf);
+
+        let row = |y| (0..3).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        assert_eq!(row(0), "───");
+        assert_eq!(row(3), "  █");
+        assert_eq!(buf[(0, 0)].fg, Color::Gray);
+    }
+}